@@ -0,0 +1,421 @@
+use crate::dual::HyperDual;
+use crate::expr::Expr;
+use crate::solver::{Iteration, Method};
+
+/// The smallest and largest sampled value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Bounds {
+    fn empty() -> Bounds {
+        Bounds {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+    fn include(&mut self, value: f64) {
+        self.min = f64::min(self.min, value);
+        self.max = f64::max(self.max, value);
+    }
+    /// Smallest absolute value on the range, zero if the range contains zero.
+    pub fn abs_min(&self) -> f64 {
+        if self.min <= 0.0 && self.max >= 0.0 {
+            0.0
+        } else {
+            f64::min(self.min.abs(), self.max.abs())
+        }
+    }
+    pub fn abs_max(&self) -> f64 {
+        f64::max(self.min.abs(), self.max.abs())
+    }
+}
+
+/// A convergence condition of the chord method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// `f` is defined and finite.
+    Defined,
+    /// `f'` keeps one sign.
+    Monotonic,
+    /// `f''` keeps one sign.
+    Convexity,
+}
+
+/// A part of [a, b] where a condition does not hold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Failure {
+    pub condition: Condition,
+    pub from: f64,
+    pub to: f64,
+}
+
+/// Sampled bounds of `f`, `f'`, `f''` on [a, b] and the chord method conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub f: Bounds,
+    pub d1: Bounds,
+    pub d2: Bounds,
+    pub sign_change: bool,
+    pub defined: bool,
+    pub monotonic: bool,
+    pub convex: bool,
+    pub failures: Vec<Failure>,
+}
+
+impl Analysis {
+    /// A-posteriori bound of `|xₙ - x*|` for the given method, using m₁ = min|f'|,
+    /// M₁ = max|f'| and M₂ = max|f''| on [a, b]. `None` when m₁ is zero.
+    pub fn error_estimate(&self, method: Method, iteration: &Iteration) -> Option<f64> {
+        let step: f64 = (iteration.x - iteration.x_prev).abs();
+        if let (Method::Bisection | Method::ChordTangent, Some((lo, hi))) =
+            (method, iteration.bracket)
+        {
+            return Some(0.5 * (hi - lo));
+        }
+        let m1: f64 = self.d1.abs_min();
+        if m1 <= 0.0 || !m1.is_finite() {
+            return None;
+        }
+        let estimate: f64 = match method {
+            Method::Chord => (self.d1.abs_max() - m1) / m1 * step,
+            Method::Newton => self.d2.abs_max() / (2.0 * m1) * step * step,
+            _ => iteration.fx.abs() / m1,
+        };
+        Some(estimate).filter(|estimate| estimate.is_finite())
+    }
+}
+
+/// The sign that covers the larger part of the samples, ignoring zeros.
+fn dominant_sign(signs: &[f64]) -> f64 {
+    let positive: usize = signs.iter().filter(|sign| **sign > 0.0).count();
+    let negative: usize = signs.iter().filter(|sign| **sign < 0.0).count();
+    if positive >= negative {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// Merges consecutive samples where `fails` holds into sub-intervals of [a, b].
+fn failing_parts(
+    condition: Condition,
+    xs: &[f64],
+    fails: impl Fn(usize) -> bool,
+    failures: &mut Vec<Failure>,
+) {
+    let step: f64 = if xs.len() > 1 { xs[1] - xs[0] } else { 0.0 };
+    let mut start: Option<f64> = None;
+    for (i, x) in xs.iter().enumerate() {
+        match (fails(i), start) {
+            (true, None) => start = Some(f64::max(xs[0], x - 0.5 * step)),
+            (false, Some(from)) => {
+                failures.push(Failure {
+                    condition,
+                    from,
+                    to: x - 0.5 * step,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        failures.push(Failure {
+            condition,
+            from,
+            to: xs[xs.len() - 1],
+        });
+    }
+}
+
+pub fn analyze(f: &Expr, a: f64, b: f64, samples: usize) -> Analysis {
+    let xs: Vec<f64> = (0..=samples)
+        .map(|i| a + (b - a) * i as f64 / samples as f64)
+        .collect();
+    let values: Vec<HyperDual> = xs.iter().map(|x| f.eval_dual(*x)).collect();
+    let finite: Vec<bool> = values
+        .iter()
+        .map(|v| v.value.is_finite() && v.d1.is_finite() && v.d2.is_finite())
+        .collect();
+    let mut bounds: [Bounds; 3] = [Bounds::empty(), Bounds::empty(), Bounds::empty()];
+    for (value, _) in values.iter().zip(&finite).filter(|(_, ok)| **ok) {
+        bounds[0].include(value.value);
+        bounds[1].include(value.d1);
+        bounds[2].include(value.d2);
+    }
+    // The extrema of f' and f'' give m₁, M₁ and M₂, so they are refined between samples.
+    let d1 = |x: f64| f.eval_dual(x).d1;
+    let d2 = |x: f64| f.eval_dual(x).d2;
+    let d1_samples: Vec<f64> = values.iter().map(|v| v.d1).collect();
+    let d2_samples: Vec<f64> = values.iter().map(|v| v.d2).collect();
+    refine_bounds(&d1, &xs, &d1_samples, &finite, &mut bounds[1]);
+    refine_bounds(&d2, &xs, &d2_samples, &finite, &mut bounds[2]);
+    let d1_signs: Vec<f64> = values.iter().map(|v| sign(v.d1)).collect();
+    let d2_signs: Vec<f64> = values.iter().map(|v| sign(v.d2)).collect();
+    let d1_sign: f64 = dominant_sign(&d1_signs);
+    let d2_sign: f64 = dominant_sign(&d2_signs);
+
+    let mut failures: Vec<Failure> = vec![];
+    failing_parts(Condition::Defined, &xs, |i| !finite[i], &mut failures);
+    failing_parts(
+        Condition::Monotonic,
+        &xs,
+        |i| finite[i] && d1_signs[i] == -d1_sign,
+        &mut failures,
+    );
+    failing_parts(
+        Condition::Convexity,
+        &xs,
+        |i| finite[i] && d2_signs[i] == -d2_sign,
+        &mut failures,
+    );
+    let holds = |condition: Condition| !failures.iter().any(|f| f.condition == condition);
+    let (fa, fb) = (values[0].value, values[samples].value);
+    Analysis {
+        f: bounds[0],
+        d1: bounds[1],
+        d2: bounds[2],
+        sign_change: fa * fb < 0.0,
+        defined: holds(Condition::Defined),
+        monotonic: holds(Condition::Monotonic) && bounds[1].abs_min() > 0.0,
+        convex: holds(Condition::Convexity),
+        failures,
+    }
+}
+
+/// Golden-section search for the minimum of `g` on [lo, hi].
+fn golden_section(g: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let ratio: f64 = (5f64.sqrt() - 1.0) / 2.0;
+    let mut x1: f64 = hi - ratio * (hi - lo);
+    let mut x2: f64 = lo + ratio * (hi - lo);
+    let (mut g1, mut g2) = (g(x1), g(x2));
+    for _ in 0..60 {
+        if g1 < g2 {
+            hi = x2;
+            (x2, g2) = (x1, g1);
+            x1 = hi - ratio * (hi - lo);
+            g1 = g(x1);
+        } else {
+            lo = x1;
+            (x1, g1) = (x2, g2);
+            x2 = lo + ratio * (hi - lo);
+            g2 = g(x2);
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Widens `bounds` with the extrema of `g` found around its smallest and largest sample.
+fn refine_bounds(
+    g: &impl Fn(f64) -> f64,
+    xs: &[f64],
+    samples: &[f64],
+    finite: &[bool],
+    bounds: &mut Bounds,
+) {
+    let defined: Vec<usize> = (0..xs.len()).filter(|i| finite[*i]).collect();
+    let smallest: Option<&usize> = defined
+        .iter()
+        .min_by(|i, j| samples[**i].total_cmp(&samples[**j]));
+    let largest: Option<&usize> = defined
+        .iter()
+        .max_by(|i, j| samples[**i].total_cmp(&samples[**j]));
+    for (index, sign) in [(smallest, 1.0), (largest, -1.0)] {
+        let Some(&i) = index else {
+            continue;
+        };
+        let lo: f64 = xs[i.saturating_sub(1)];
+        let hi: f64 = xs[usize::min(i + 1, xs.len() - 1)];
+        let value: f64 = g(golden_section(|x| sign * g(x), lo, hi));
+        if value.is_finite() {
+            bounds.include(value);
+        }
+    }
+}
+
+fn sign(value: f64) -> f64 {
+    if value > 0.0 {
+        1.0
+    } else if value < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// What a localised sub-interval most likely contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketKind {
+    /// `f` changes sign, so there is a root of odd multiplicity.
+    SignChange,
+    /// `|f|` has a local minimum close to zero without a sign change: a suspected root
+    /// of even multiplicity that the chord method cannot bracket.
+    EvenMultiplicity,
+}
+
+/// A sub-interval that should contain exactly one root.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bracket {
+    pub a: f64,
+    pub b: f64,
+    pub kind: BracketKind,
+}
+
+/// How many times a suspicious minimum of `|f|` is tabulated again on a finer grid.
+const MAX_REFINEMENTS: usize = 8;
+/// Odd, so the refined grid never repeats the node it is centred on.
+const REFINEMENT_STEPS: usize = 9;
+
+/// Localises the roots on [from, to]: tabulates `f` with `steps` cells and
+/// refines the grid around local minima of `|f|` that do not change sign.
+pub fn find_brackets(f: &Expr, from: f64, to: f64, steps: usize) -> Vec<Bracket> {
+    let mut brackets: Vec<Bracket> = vec![];
+    let scale: f64 = (0..=steps)
+        .map(|i| f.eval(from + (to - from) * i as f64 / steps as f64).abs())
+        .filter(|y| y.is_finite())
+        .fold(0.0, f64::max);
+    tabulate(f, from, to, steps, 0, scale, &mut brackets);
+    brackets.sort_by(|left, right| left.a.total_cmp(&right.a));
+    brackets
+}
+
+fn tabulate(
+    f: &Expr,
+    from: f64,
+    to: f64,
+    steps: usize,
+    depth: usize,
+    scale: f64,
+    brackets: &mut Vec<Bracket>,
+) {
+    let xs: Vec<f64> = (0..=steps)
+        .map(|i| from + (to - from) * i as f64 / steps as f64)
+        .collect();
+    let ys: Vec<f64> = xs.iter().map(|x| f.eval(*x)).collect();
+    for i in 0..steps {
+        let (y0, y1) = (ys[i], ys[i + 1]);
+        if !y0.is_finite() || !y1.is_finite() {
+            continue;
+        }
+        // An exact zero at a node belongs to the cell on its left, except at the very start.
+        let changes: bool = y0 * y1 < 0.0 || y1 == 0.0 || (i == 0 && y0 == 0.0);
+        if !changes {
+            continue;
+        }
+        if y1 == 0.0 && i + 2 <= steps && y0 * ys[i + 2] > 0.0 {
+            // A zero that touches the axis, or two roots closer than the grid.
+            if depth < MAX_REFINEMENTS {
+                tabulate(
+                    f,
+                    xs[i],
+                    xs[i + 2],
+                    REFINEMENT_STEPS,
+                    depth + 1,
+                    scale,
+                    brackets,
+                );
+            } else {
+                brackets.push(Bracket {
+                    a: xs[i],
+                    b: xs[i + 2],
+                    kind: BracketKind::EvenMultiplicity,
+                });
+            }
+            continue;
+        }
+        // Across a pole |f| grows towards the middle instead of falling.
+        let mid: f64 = f.eval(0.5 * (xs[i] + xs[i + 1]));
+        if mid.is_finite() && mid.abs() <= f64::max(y0.abs(), y1.abs()) {
+            brackets.push(Bracket {
+                a: xs[i],
+                b: xs[i + 1],
+                kind: BracketKind::SignChange,
+            });
+        }
+    }
+    for i in 1..steps {
+        let (left, y, right) = (ys[i - 1], ys[i], ys[i + 1]);
+        if !left.is_finite() || !y.is_finite() || !right.is_finite() {
+            continue;
+        }
+        let local_minimum: bool = y.abs() < left.abs() && y.abs() <= right.abs();
+        if !local_minimum || y == 0.0 || left * y <= 0.0 || y * right <= 0.0 {
+            continue;
+        }
+        if depth < MAX_REFINEMENTS {
+            tabulate(
+                f,
+                xs[i - 1],
+                xs[i + 1],
+                REFINEMENT_STEPS,
+                depth + 1,
+                scale,
+                brackets,
+            );
+        } else if y.abs() <= 1e-6 * scale {
+            brackets.push(Bracket {
+                a: xs[i - 1],
+                b: xs[i + 1],
+                kind: BracketKind::EvenMultiplicity,
+            });
+        }
+    }
+}
+
+/// Step sizes and residuals of a run, with the empirical order of convergence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Convergence {
+    /// `[n, |xₙ − xₙ₋₁|]` for every iteration with a previous iterate.
+    pub steps: Vec<[f64; 2]>,
+    /// `[n, |f(xₙ)|]`.
+    pub residuals: Vec<[f64; 2]>,
+    /// `[n, p]`: p ≈ ln(eₙ/eₙ₋₁) / ln(eₙ₋₁/eₙ₋₂) with eₙ = |xₙ − xₙ₋₁|.
+    pub orders: Vec<[f64; 2]>,
+    /// The latest order estimate.
+    pub order: Option<f64>,
+    /// The asymptotic rate constant C ≈ eₙ / eₙ₋₁ᵖ for the latest order.
+    pub rate: Option<f64>,
+}
+
+pub fn convergence(history: &[Iteration]) -> Convergence {
+    let steps: Vec<[f64; 2]> = history
+        .iter()
+        .map(|iteration| [iteration.n as f64, (iteration.x - iteration.x_prev).abs()])
+        .filter(|[_, step]| step.is_finite())
+        .collect();
+    let residuals: Vec<[f64; 2]> = history
+        .iter()
+        .map(|iteration| [iteration.n as f64, iteration.fx.abs()])
+        .collect();
+    // Steps this close to the rounding error of x say nothing about the order.
+    let noise: f64 = history
+        .iter()
+        .map(|iteration| iteration.x.abs())
+        .filter(|x| x.is_finite())
+        .fold(1.0, f64::max)
+        * 1e3
+        * f64::EPSILON;
+    let mut orders: Vec<[f64; 2]> = vec![];
+    let mut rate: Option<f64> = None;
+    for window in steps.windows(3) {
+        let (e0, e1, e2) = (window[0][1], window[1][1], window[2][1]);
+        if e2 <= noise {
+            break;
+        }
+        let order: f64 = (e2 / e1).ln() / (e1 / e0).ln();
+        if order.is_finite() {
+            orders.push([window[2][0], order]);
+            rate = Some(e2 / e1.powf(order)).filter(|rate| rate.is_finite());
+        }
+    }
+    Convergence {
+        order: orders.last().map(|[_, order]| *order),
+        steps,
+        residuals,
+        orders,
+        rate,
+    }
+}
@@ -0,0 +1,400 @@
+use crate::analysis::{self, Analysis};
+use crate::export::{self, ExportOptions, Format, Report};
+use crate::expr::{self, Expr};
+use crate::solver::{self, Iteration, Method, RootSolver, SolverError};
+use crate::stop::{StopCriteria, StopRule};
+use std::io::{ErrorKind, Write};
+
+/// Wrong or missing arguments.
+const USAGE_ERROR: i32 = 2;
+/// The equation does not parse.
+const PARSE_ERROR: i32 = 3;
+/// The output could not be written.
+const OUTPUT_ERROR: i32 = 4;
+/// The stop rules held, but the conditions of the method or the error bound do not.
+const UNRELIABLE: i32 = 18;
+
+const USAGE: &str = "\
+Usage: opr solve --equation <f(x)> --a <a> --b <b> [options]
+
+Solves f(x) = 0 on [a, b] without opening the window and prints the iterations.
+
+Options:
+  --equation <f(x)>       the left-hand side, for example \"x^2 - 5*sin(x)\"
+  --a <a>, --b <b>        the interval
+  --eps <eps>             the tolerance, 0.001 by default
+  --method <method>       chord (default), bisection, newton, secant, regula-falsi,
+                          illinois, ridders, brent or chord-tangent
+  --max-iterations <n>    the iteration cap, 1000 by default
+  --stop <rules>          when to stop, comma-separated: step (default), bound,
+                          residual, relative
+  --all                   stop when all of the rules hold instead of any of them
+  --delta <delta>         the residual tolerance |f(x)| < delta, 1e-6 by default
+  --precision <digits>    digits after the decimal point, 6 by default
+  --json                  print JSON instead of a table
+
+Exit status:
+  0   the tolerance was reached
+  2   wrong arguments
+  3   the equation does not parse
+  4   the output could not be written
+  10  no sign change on [a, b]
+  11  the iterate left the interval
+  12  f'' changes sign
+  13  division by a near-zero chord slope
+  14  division by a near-zero derivative
+  15  f is undefined
+  16  iteration limit reached
+  18  the answer is not reliable: f' or f'' changes sign on [a, b] or the error
+      bound exceeds eps; the warnings go to stderr";
+
+/// What `opr solve` was asked to do.
+struct Arguments {
+    equation: String,
+    a: f64,
+    b: f64,
+    eps: f64,
+    method: Method,
+    max_iterations: usize,
+    stop: StopCriteria,
+    precision: usize,
+    json: bool,
+}
+
+fn parse_arguments(args: &[String]) -> Result<Arguments, String> {
+    let mut equation: Option<String> = None;
+    let mut a: Option<f64> = None;
+    let mut b: Option<f64> = None;
+    let mut arguments: Arguments = Arguments {
+        equation: String::new(),
+        a: 0.0,
+        b: 0.0,
+        eps: 0.001,
+        method: Method::Chord,
+        max_iterations: solver::MAX_ITERATIONS,
+        stop: StopCriteria::default(),
+        precision: 6,
+        json: false,
+    };
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--json" {
+            arguments.json = true;
+            continue;
+        }
+        if flag == "--all" {
+            arguments.stop.require_all = true;
+            continue;
+        }
+        let value: &String = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?;
+        let number = || -> Result<f64, String> {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| format!("{} expects a number, got \"{}\"", flag, value))
+        };
+        let count = || -> Result<usize, String> {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("{} expects a whole number, got \"{}\"", flag, value))
+        };
+        match flag.as_str() {
+            "--equation" => equation = Some(value.clone()),
+            "--a" => a = Some(number()?),
+            "--b" => b = Some(number()?),
+            "--eps" => arguments.eps = number()?,
+            "--method" => {
+                arguments.method = Method::from_key(value.to_lowercase().as_str())
+                    .ok_or_else(|| format!("unknown method \"{}\"", value))?
+            }
+            "--max-iterations" => arguments.max_iterations = count()?,
+            "--stop" => {
+                arguments.stop.rules = value
+                    .split(',')
+                    .map(|key| {
+                        StopRule::ALL
+                            .into_iter()
+                            .find(|rule| rule.key() == key.trim())
+                            .ok_or_else(|| format!("unknown stop rule \"{}\"", key))
+                    })
+                    .collect::<Result<Vec<StopRule>, String>>()?
+            }
+            "--delta" => arguments.stop.delta = number()?,
+            "--precision" => arguments.precision = count()?.min(17),
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+    arguments.equation = equation.ok_or("--equation is required")?;
+    arguments.a = a.ok_or("--a is required")?;
+    arguments.b = b.ok_or("--b is required")?;
+    if arguments.a >= arguments.b {
+        return Err("a must be less than b".to_string());
+    }
+    if arguments.eps <= 0.0 {
+        return Err("eps must be positive".to_string());
+    }
+    Ok(arguments)
+}
+
+/// Why the answer cannot be trusted even though the stop rules held.
+fn warnings(analysis: &Analysis, method: Method, last: &Iteration, eps: f64) -> Vec<String> {
+    let mut warnings: Vec<String> = vec![];
+    if !analysis.defined {
+        warnings.push("f is undefined somewhere on [a, b]".to_string());
+    }
+    // The chord method and its combination with tangents rely on both conditions.
+    if matches!(method, Method::Chord | Method::ChordTangent) {
+        if !analysis.monotonic {
+            warnings.push("f' changes sign on [a, b], the root may not be unique".to_string());
+        }
+        if !analysis.convex {
+            warnings.push(
+                "f'' changes sign on [a, b], the method may converge to a wrong point".to_string(),
+            );
+        }
+    }
+    if let Some(bound) = analysis.error_estimate(method, last) {
+        if bound > eps {
+            warnings.push(format!(
+                "the error bound |x - x*| <= {:e} exceeds eps = {:e}",
+                bound, eps
+            ));
+        }
+    }
+    warnings
+}
+
+/// Writes to stdout. A reader that went away, as in `opr solve ... | head`, is not an error.
+fn print(text: &str) -> Result<(), i32> {
+    let mut stdout = std::io::stdout().lock();
+    match stdout
+        .write_all(text.as_bytes())
+        .and_then(|()| stdout.flush())
+    {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == ErrorKind::BrokenPipe => Ok(()),
+        Err(error) => {
+            eprintln!("opr solve: {}", error);
+            Err(OUTPUT_ERROR)
+        }
+    }
+}
+
+/// `opr solve ...`: runs one method to the end, reports it on stdout and returns the exit status.
+pub fn solve(args: &[String]) -> i32 {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return print(&format!("{}\n", USAGE)).err().unwrap_or(0);
+    }
+    let arguments: Arguments = match parse_arguments(args) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("opr solve: {}\n\n{}", message, USAGE);
+            return USAGE_ERROR;
+        }
+    };
+    let expr: Expr = match expr::parse(arguments.equation.as_str()) {
+        Ok(expr) => expr,
+        Err(error) => {
+            eprintln!(
+                "opr solve: parse error at {}\n{}\n{}^",
+                error,
+                arguments.equation,
+                " ".repeat(arguments.equation.chars().count().min(error.position))
+            );
+            return PARSE_ERROR;
+        }
+    };
+    let mut solver: Box<dyn RootSolver> =
+        arguments
+            .method
+            .solver(arguments.a, arguments.b, arguments.eps);
+    solver.set_limits(arguments.eps, arguments.max_iterations);
+    let analysis: Analysis = analysis::analyze(&expr, arguments.a, arguments.b, 1000);
+    let mut history: Vec<Iteration> = vec![];
+    let error: Option<SolverError> = loop {
+        match solver.step(&expr) {
+            Ok(iteration) => {
+                let stops: bool =
+                    arguments
+                        .stop
+                        .stops(arguments.method, &iteration, &analysis, arguments.eps);
+                history.push(iteration);
+                if stops {
+                    break None;
+                }
+            }
+            Err(error) => break Some(error),
+        }
+    };
+    let report: Report = Report {
+        title: arguments.equation.trim(),
+        method: arguments.method.name(),
+        a: arguments.a,
+        b: arguments.b,
+        eps: arguments.eps,
+        history: &history,
+        estimates: history
+            .iter()
+            .map(|iteration| analysis.error_estimate(arguments.method, iteration))
+            .collect(),
+        stops: history
+            .iter()
+            .map(|iteration| {
+                arguments
+                    .stop
+                    .stops(arguments.method, iteration, &analysis, arguments.eps)
+            })
+            .collect(),
+        error: error.as_ref(),
+    };
+    let options: ExportOptions = ExportOptions {
+        format: if arguments.json {
+            Format::Json
+        } else {
+            Format::Csv
+        },
+        precision: arguments.precision,
+        ..ExportOptions::default()
+    };
+    let mut text: String = if arguments.json {
+        export::render(&report, &options)
+    } else {
+        export::text(&report, &options)
+    };
+    if !arguments.json {
+        match (&error, history.last()) {
+            (Some(error), _) => text += &format!("\nError: {}\n{}\n", error.title(), error),
+            (None, Some(last)) => {
+                text += &format!(
+                    "\nx = {:.2$}, f(x) = {:.3e}\n",
+                    last.x, last.fx, arguments.precision
+                )
+            }
+            (None, None) => {}
+        }
+    }
+    if let Err(status) = print(&text) {
+        return status;
+    }
+    match (error, history.last()) {
+        (Some(error), _) => {
+            if arguments.json {
+                eprintln!("opr solve: {}", error.title());
+            }
+            error.exit_code()
+        }
+        (None, Some(last)) => {
+            let warnings: Vec<String> = warnings(&analysis, arguments.method, last, arguments.eps);
+            for warning in warnings.iter() {
+                eprintln!("opr solve: warning: {}", warning);
+            }
+            if warnings.is_empty() {
+                0
+            } else {
+                UNRELIABLE
+            }
+        }
+        (None, None) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn error(text: &str) -> String {
+        parse_arguments(&args(text)).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn reads_every_option() {
+        let arguments: Arguments = parse_arguments(&args(
+            "--equation x^2-2 --a 1 --b 2 --eps 1e-9 --method Newton --max-iterations 40 \
+             --stop step,residual --all --delta 1e-12 --precision 30 --json",
+        ))
+        .unwrap();
+        assert_eq!(arguments.equation, "x^2-2");
+        assert_eq!((arguments.a, arguments.b, arguments.eps), (1.0, 2.0, 1e-9));
+        assert_eq!(arguments.method, Method::Newton);
+        assert_eq!(arguments.max_iterations, 40);
+        assert_eq!(
+            arguments.stop,
+            StopCriteria {
+                rules: vec![StopRule::Step, StopRule::Residual],
+                require_all: true,
+                delta: 1e-12,
+            }
+        );
+        assert_eq!(arguments.precision, 17);
+        assert!(arguments.json);
+    }
+
+    #[test]
+    fn rejects_wrong_arguments() {
+        assert_eq!(error("--a 1 --b 2"), "--equation is required");
+        assert_eq!(error("--equation x --b 2"), "--a is required");
+        assert_eq!(error("--equation x --a 1"), "--b is required");
+        assert_eq!(error("--equation x --a 2 --b 1"), "a must be less than b");
+        assert_eq!(
+            error("--equation x --a 1 --b 2 --eps 0"),
+            "eps must be positive"
+        );
+        assert_eq!(
+            error("--equation x --a one --b 2"),
+            "--a expects a number, got \"one\""
+        );
+        assert_eq!(
+            error("--equation x --a inf --b 2"),
+            "--a expects a number, got \"inf\""
+        );
+        assert_eq!(
+            error("--equation x --a 1 --b 2 --max-iterations -1"),
+            "--max-iterations expects a whole number, got \"-1\""
+        );
+        assert_eq!(
+            error("--equation x --a 1 --b 2 --method golden"),
+            "unknown method \"golden\""
+        );
+        assert_eq!(
+            error("--equation x --a 1 --b 2 --stop step,size"),
+            "unknown stop rule \"size\""
+        );
+        assert_eq!(
+            error("--equation x --a 1 --b 2 --verbose 1"),
+            "unknown option --verbose"
+        );
+        assert_eq!(error("--equation x --a 1 --b"), "--b needs a value");
+    }
+
+    #[test]
+    fn exit_codes() {
+        let status = |text: &str| solve(&args(text));
+        assert_eq!(status("--help"), 0);
+        assert_eq!(status("--equation x --a 1"), USAGE_ERROR);
+        assert_eq!(status("--equation x^ --a 1 --b 2"), PARSE_ERROR);
+        assert_eq!(status("--equation x^2-2 --a 1 --b 2 --eps 1e-9"), 0);
+        assert_eq!(status("--equation x^2+1 --a 1 --b 2"), 10);
+        assert_eq!(
+            status("--equation x^2-2 --a 1 --b 2 --eps 1e-9 --max-iterations 2"),
+            16
+        );
+        // x³ on [−1, 2]: f'(0) = 0 and f'' changes sign, so the root is not to be trusted.
+        assert_eq!(status("--equation x^3 --a -1 --b 2"), UNRELIABLE);
+        // A loose residual rule stops before the error bound is within eps.
+        assert_eq!(
+            status("--equation x^2-2 --a 1 --b 2 --stop residual --delta 0.5"),
+            UNRELIABLE
+        );
+        assert_eq!(
+            status("--equation x^2-2 --a 1 --b 2 --eps 1e-9 --stop bound,residual --all"),
+            0
+        );
+    }
+}
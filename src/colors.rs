@@ -62,6 +62,19 @@ pub struct Theme {
 }
 
 impl Theme {
+    /// Distinct accent colors for plotting several series side by side.
+    pub fn palette(&self) -> [Color32; 8] {
+        [
+            self.blue,
+            self.mauve,
+            self.peach,
+            self.teal,
+            self.yellow,
+            self.flamingo,
+            self.sapphire,
+            self.maroon,
+        ]
+    }
     fn visuals(&self, old: egui::Visuals) -> egui::Visuals {
         let is_latte = *self == LATTE;
         egui::Visuals {
@@ -106,6 +119,14 @@ impl Theme {
     }
 }
 
+/// The theme variants by the names shown on the theme button.
+pub const THEMES: [(&str, Theme); 4] = [
+    ("LATTE", LATTE),
+    ("FRAPPE", FRAPPE),
+    ("MACCHIATO", MACCHIATO),
+    ("MOCHA", MOCHA),
+];
+
 pub const LATTE: Theme = Theme {
     rosewater: Color32::from_rgb(220, 138, 120),
     flamingo: Color32::from_rgb(221, 120, 120),
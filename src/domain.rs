@@ -0,0 +1,485 @@
+use crate::expr::{BinaryOp, Expr, Func};
+use std::fmt;
+
+/// One end of an interval; infinite ends are always open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct End {
+    pub value: f64,
+    pub closed: bool,
+}
+
+impl End {
+    pub fn open(value: f64) -> End {
+        End {
+            value,
+            closed: false,
+        }
+    }
+    pub fn closed(value: f64) -> End {
+        End {
+            value,
+            closed: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: End,
+    pub hi: End,
+}
+
+impl Interval {
+    pub fn contains(&self, x: f64) -> bool {
+        let above: bool = x > self.lo.value || (self.lo.closed && x == self.lo.value);
+        let below: bool = x < self.hi.value || (self.hi.closed && x == self.hi.value);
+        above && below
+    }
+    /// The point of the interval closest to `x`, a little inside at open ends.
+    fn clamp(&self, x: f64) -> f64 {
+        let width: f64 = self.hi.value - self.lo.value;
+        let inside = |end: End, direction: f64| -> f64 {
+            if end.closed {
+                end.value
+            } else {
+                let margin: f64 = f64::min(1e-6 * f64::max(1.0, end.value.abs()), 0.5 * width);
+                end.value + direction * margin
+            }
+        };
+        if x < self.lo.value || (x == self.lo.value && !self.lo.closed) {
+            inside(self.lo, 1.0)
+        } else if x > self.hi.value || (x == self.hi.value && !self.hi.closed) {
+            inside(self.hi, -1.0)
+        } else {
+            x
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: f64| -> String {
+            if value == f64::INFINITY {
+                "+∞".to_string()
+            } else if value == f64::NEG_INFINITY {
+                "−∞".to_string()
+            } else {
+                format!("{}", value)
+            }
+        };
+        write!(
+            f,
+            "{}{}; {}{}",
+            if self.lo.closed { "[" } else { "(" },
+            value(self.lo.value),
+            value(self.hi.value),
+            if self.hi.closed { "]" } else { ")" }
+        )
+    }
+}
+
+/// Where `f` is defined: disjoint intervals in increasing order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Domain {
+    pub intervals: Vec<Interval>,
+}
+
+impl Domain {
+    pub fn interval(lo: End, hi: End) -> Domain {
+        Domain {
+            intervals: vec![Interval { lo, hi }],
+        }
+    }
+    pub fn real_line() -> Domain {
+        Domain::interval(End::open(f64::NEG_INFINITY), End::open(f64::INFINITY))
+    }
+    pub fn is_real_line(&self) -> bool {
+        *self == Domain::real_line()
+    }
+    /// The point of the domain closest to `x`.
+    pub fn clamp(&self, x: f64) -> f64 {
+        self.intervals
+            .iter()
+            .map(|interval| interval.clamp(x))
+            .min_by(|p, q| (p - x).abs().total_cmp(&(q - x).abs()))
+            .unwrap_or(x)
+    }
+    /// `[a, b]` moved into the domain, kept inside one interval so that `f` is continuous on it.
+    pub fn bracket(&self, a: f64, b: f64) -> (f64, f64) {
+        let a: f64 = self.clamp(a);
+        let Some(interval) = self.intervals.iter().find(|interval| interval.contains(a)) else {
+            return (a, b);
+        };
+        let b: f64 = interval.clamp(b);
+        if b > a {
+            return (a, b);
+        }
+        // Both ends fell onto the same point: take a unit-wide piece of the interval instead.
+        let b: f64 = interval.clamp(a + 1.0);
+        if b > a {
+            (a, b)
+        } else {
+            (interval.clamp(a - 1.0), a)
+        }
+    }
+}
+
+impl std::str::FromStr for Domain {
+    type Err = String;
+    /// Reads the notation `Display` writes, like `[-2; 2]` or `(-inf; 1) U (1; +inf)`;
+    /// `∞`, `inf`, `∪` and `U` are all accepted, and `,` may separate the ends.
+    fn from_str(text: &str) -> Result<Domain, String> {
+        let end = |text: &str| -> Result<f64, String> {
+            match text.trim().replace('−', "-").as_str() {
+                "-inf" | "-∞" => Ok(f64::NEG_INFINITY),
+                "inf" | "+inf" | "∞" | "+∞" => Ok(f64::INFINITY),
+                number => number
+                    .parse::<f64>()
+                    .map_err(|_| format!("\"{}\" is not a number", number)),
+            }
+        };
+        let mut intervals: Vec<Interval> = vec![];
+        for part in text.split(['∪', 'U']) {
+            let part: &str = part.trim();
+            let (Some(open), Some(close)) = (part.chars().next(), part.chars().last()) else {
+                return Err("an empty interval".to_string());
+            };
+            if !matches!(open, '[' | '(') || !matches!(close, ']' | ')') || part.len() < 2 {
+                return Err(format!("\"{}\" is not an interval", part));
+            }
+            let inner: &str = &part[open.len_utf8()..part.len() - close.len_utf8()];
+            let Some((lo, hi)) = inner.split_once([';', ',']) else {
+                return Err(format!("\"{}\" needs two ends", part));
+            };
+            let (lo, hi): (f64, f64) = (end(lo)?, end(hi)?);
+            if !(lo < hi || (lo == hi && open == '[' && close == ']')) {
+                return Err(format!("\"{}\" is empty", part));
+            }
+            let interval: Interval = Interval {
+                lo: End {
+                    value: lo,
+                    closed: open == '[' && lo.is_finite(),
+                },
+                hi: End {
+                    value: hi,
+                    closed: close == ']' && hi.is_finite(),
+                },
+            };
+            if intervals
+                .last()
+                .is_some_and(|last| last.hi.value > interval.lo.value)
+            {
+                return Err("intervals must be disjoint and in increasing order".to_string());
+            }
+            intervals.push(interval);
+        }
+        Ok(Domain { intervals })
+    }
+}
+
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.intervals.is_empty() {
+            return write!(f, "∅");
+        }
+        // Periodic poles give endless intervals; only the ones near the origin are listed then.
+        let many: bool = self.intervals.len() > 6;
+        let mut parts: Vec<String> = vec![];
+        for interval in self.intervals.iter() {
+            if !many || (interval.lo.value < 10.0 && interval.hi.value > -10.0) {
+                parts.push(interval.to_string());
+            } else if parts.last().is_none_or(|last| last != "…") {
+                parts.push("…".to_string());
+            }
+        }
+        write!(f, "{}", parts.join(" ∪ "))
+    }
+}
+
+/// What an operation requires of its argument.
+enum Constraint {
+    /// `g(x) > 0`, as for `ln`.
+    Positive(Expr),
+    /// `g(x) ≥ 0`, as for `sqrt`.
+    NonNegative(Expr),
+    /// `g(x) ≠ 0`, as for a divisor.
+    NonZero(Expr),
+}
+
+impl Constraint {
+    fn holds(&self, x: f64) -> bool {
+        match self {
+            Constraint::Positive(g) => g.eval(x) > 0.0,
+            Constraint::NonNegative(g) => g.eval(x) >= 0.0,
+            // Where g itself is undefined, its own constraints fail instead.
+            Constraint::NonZero(g) => g.eval(x) != 0.0,
+        }
+    }
+}
+
+/// The value of `expr` when it does not depend on `x`.
+fn constant(expr: &Expr) -> Option<f64> {
+    fn has_variable(expr: &Expr) -> bool {
+        match expr {
+            Expr::Number(_) => false,
+            Expr::Variable => true,
+            Expr::Neg(inner) | Expr::Call(_, inner) => has_variable(inner),
+            Expr::Binary(_, left, right) => has_variable(left) || has_variable(right),
+        }
+    }
+    (!has_variable(expr)).then(|| expr.eval(0.0))
+}
+
+fn constraints(expr: &Expr, found: &mut Vec<Constraint>) {
+    match expr {
+        Expr::Number(_) | Expr::Variable => {}
+        Expr::Neg(inner) => constraints(inner, found),
+        Expr::Binary(op, left, right) => {
+            constraints(left, found);
+            constraints(right, found);
+            match (op, constant(right)) {
+                (BinaryOp::Div, _) => found.push(Constraint::NonZero(*right.clone())),
+                // Integer powers are defined everywhere, negative ones except at zero.
+                (BinaryOp::Pow, Some(c)) if c.fract() == 0.0 && c < 0.0 => {
+                    found.push(Constraint::NonZero(*left.clone()))
+                }
+                (BinaryOp::Pow, Some(c)) if c.fract() == 0.0 => {}
+                (BinaryOp::Pow, Some(c)) if c > 0.0 => {
+                    found.push(Constraint::NonNegative(*left.clone()))
+                }
+                (BinaryOp::Pow, _) => found.push(Constraint::Positive(*left.clone())),
+                _ => {}
+            }
+        }
+        Expr::Call(func, arg) => {
+            constraints(arg, found);
+            let arg: Expr = *arg.clone();
+            match func {
+                Func::Ln | Func::Log10 => found.push(Constraint::Positive(arg)),
+                Func::Sqrt => found.push(Constraint::NonNegative(arg)),
+                Func::Asin | Func::Acos => found.push(Constraint::NonNegative(Expr::Binary(
+                    BinaryOp::Sub,
+                    Box::new(Expr::Number(1.0)),
+                    Box::new(Expr::Binary(
+                        BinaryOp::Mul,
+                        Box::new(arg.clone()),
+                        Box::new(arg),
+                    )),
+                ))),
+                Func::Tan => found.push(Constraint::NonZero(Expr::Call(Func::Cos, Box::new(arg)))),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Tabulation nodes: fine around the origin, dense enough for periodic poles out to ±1000,
+/// then geometrically wider out to ±10⁴.
+fn scan_grid() -> Vec<f64> {
+    let mut xs: Vec<f64> = (-4000..=4000).map(|i| i as f64 / 400.0).collect();
+    for i in 201..=20000 {
+        let x: f64 = i as f64 / 20.0;
+        xs.push(x);
+        xs.push(-x);
+    }
+    for i in 1..=400 {
+        let x: f64 = 10f64.powf(3.0 + i as f64 / 400.0);
+        xs.push(x);
+        xs.push(-x);
+    }
+    xs.sort_by(f64::total_cmp);
+    xs
+}
+
+/// The number with the fewest significant digits within a few ulps of `x`. Bisection noise
+/// is rounded away, so that boundaries like 3 come out exact, while a boundary of any
+/// magnitude or precision, like 10⁻¹² or 1.23456789012, keeps its value.
+fn snap(x: f64) -> f64 {
+    let tolerance: f64 = 4.0 * f64::EPSILON * x.abs();
+    (1..=17)
+        .filter_map(|digits: usize| format!("{:.1$e}", x, digits - 1).parse::<f64>().ok())
+        .find(|rounded| (rounded - x).abs() <= tolerance)
+        .unwrap_or(x)
+}
+
+/// Finds the domain of `expr` from the requirements of `ln`, `sqrt`, division, powers,
+/// `asin`, `acos` and `tan`, locating their boundaries by tabulation and bisection.
+pub fn infer(expr: &Expr) -> Domain {
+    let mut found: Vec<Constraint> = vec![];
+    constraints(expr, &mut found);
+    if found.is_empty() {
+        return Domain::real_line();
+    }
+    let holds = |x: f64| found.iter().all(|constraint| constraint.holds(x));
+    // A boundary is closed when the requirement broken just outside it is `≥ 0`.
+    let end = |inside: f64, outside: f64| -> End {
+        let (mut inside, mut outside) = (inside, outside);
+        for _ in 0..100 {
+            let mid: f64 = 0.5 * (inside + outside);
+            if holds(mid) {
+                inside = mid;
+            } else {
+                outside = mid;
+            }
+        }
+        let closed: bool = found.iter().all(|constraint| {
+            constraint.holds(outside) || matches!(constraint, Constraint::NonNegative(_))
+        });
+        if closed {
+            End::closed(if holds(snap(inside)) {
+                snap(inside)
+            } else {
+                inside
+            })
+        } else {
+            End::open(snap(outside))
+        }
+    };
+    let xs: Vec<f64> = scan_grid();
+    let mut intervals: Vec<Interval> = vec![];
+    let mut lo: Option<End> = holds(xs[0]).then_some(End::open(f64::NEG_INFINITY));
+    for pair in xs.windows(2) {
+        let (x0, x1) = (pair[0], pair[1]);
+        match (holds(x0), holds(x1), lo) {
+            (false, true, _) => lo = Some(end(x1, x0)),
+            (true, false, Some(start)) => {
+                intervals.push(Interval {
+                    lo: start,
+                    hi: end(x0, x1),
+                });
+                lo = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = lo {
+        intervals.push(Interval {
+            lo: start,
+            hi: End::open(f64::INFINITY),
+        });
+    }
+    // Zeros of divisors between two nodes are single points cut out of an interval.
+    let mut zeros: Vec<f64> = vec![];
+    for constraint in found.iter() {
+        let Constraint::NonZero(g) = constraint else {
+            continue;
+        };
+        for pair in xs.windows(2) {
+            let (mut x0, mut x1) = (pair[0], pair[1]);
+            let (g0, g1) = (g.eval(x0), g.eval(x1));
+            if g0 * g1 >= 0.0 || g0.is_nan() || g1.is_nan() || !holds(x0) || !holds(x1) {
+                continue;
+            }
+            for _ in 0..100 {
+                let mid: f64 = 0.5 * (x0 + x1);
+                if g.eval(mid) * g0 > 0.0 {
+                    x0 = mid;
+                } else {
+                    x1 = mid;
+                }
+            }
+            // A sign change across a pole of g is not a zero.
+            if g.eval(x0).abs() > 1e-6 * f64::max(g0.abs(), g1.abs()) {
+                continue;
+            }
+            zeros.push(snap(0.5 * (x0 + x1)));
+        }
+    }
+    // Sorted once, the zeros are cut out in a single pass even for the many poles of tan.
+    zeros.sort_by(f64::total_cmp);
+    zeros.dedup();
+    let mut zeros = zeros.into_iter().peekable();
+    let mut cut: Vec<Interval> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        let mut lo: End = interval.lo;
+        while let Some(zero) = zeros.next_if(|zero| *zero <= interval.hi.value) {
+            if interval.contains(zero) {
+                cut.push(Interval {
+                    lo,
+                    hi: End::open(zero),
+                });
+                lo = End::open(zero);
+            }
+        }
+        cut.push(Interval {
+            lo,
+            hi: interval.hi,
+        });
+    }
+    Domain { intervals: cut }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+
+    fn domain(input: &str) -> Domain {
+        infer(&expr::parse(input).unwrap())
+    }
+
+    #[test]
+    fn ln_needs_a_positive_argument() {
+        assert_eq!(
+            domain("ln(x)"),
+            Domain::interval(End::open(0.0), End::open(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn sqrt_includes_zero() {
+        assert_eq!(
+            domain("sqrt(x - 2)"),
+            Domain::interval(End::closed(2.0), End::open(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn boundaries_keep_their_digits() {
+        let from = |input: &str| domain(input).intervals[0].lo;
+        assert_eq!(from("ln(x - 1e-12)"), End::open(1e-12));
+        assert_eq!(from("ln(x - 1.23456789012)"), End::open(1.23456789012));
+        // 0.1·x − 0.3 is rounded to zero a few ulps away from 3.
+        assert_eq!(from("sqrt(0.1*x - 0.3)"), End::closed(3.0));
+    }
+
+    #[test]
+    fn division_cuts_out_zero() {
+        assert_eq!(
+            domain("1/x").intervals,
+            vec![
+                Interval {
+                    lo: End::open(f64::NEG_INFINITY),
+                    hi: End::open(0.0),
+                },
+                Interval {
+                    lo: End::open(0.0),
+                    hi: End::open(f64::INFINITY),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tan_is_cut_at_every_pole() {
+        let tan: Domain = domain("tan(x)");
+        let half_pi: f64 = std::f64::consts::FRAC_PI_2;
+        let poles: usize = tan.intervals.len() - 1;
+        // Every pole within ±1000 is found: 2·⌊1000/π + 1/2⌋ of them.
+        assert!(poles >= 636, "{} poles", poles);
+        for pair in tan.intervals.windows(2) {
+            assert!(pair[0].hi.value < pair[1].lo.value || pair[0].hi == pair[1].lo);
+            let k: f64 = (pair[0].hi.value - half_pi) / std::f64::consts::PI;
+            assert!(
+                (k - k.round()).abs() < 1e-6,
+                "{} is not a pole",
+                pair[0].hi.value
+            );
+        }
+        assert!(tan
+            .intervals
+            .iter()
+            .any(|interval| (interval.hi.value - half_pi).abs() < 1e-6));
+        assert!(tan.intervals.iter().any(|interval| interval.contains(0.0)));
+    }
+}
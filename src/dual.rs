@@ -0,0 +1,141 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A value together with its first and second derivative with respect to `x`.
+///
+/// Arithmetic on it follows the chain rule, so evaluating an expression on
+/// `HyperDual::variable(x)` gives `f(x)`, `f'(x)` and `f''(x)` in a single pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HyperDual {
+    pub value: f64,
+    pub d1: f64,
+    pub d2: f64,
+}
+
+impl HyperDual {
+    pub fn constant(value: f64) -> HyperDual {
+        HyperDual {
+            value,
+            d1: 0.0,
+            d2: 0.0,
+        }
+    }
+    pub fn variable(x: f64) -> HyperDual {
+        HyperDual {
+            value: x,
+            d1: 1.0,
+            d2: 0.0,
+        }
+    }
+    /// Applies `g` to `self`, given `g(u)`, `g'(u)` and `g''(u)` at `u = self.value`.
+    pub fn chain(self, g: f64, g_der: f64, g_der2: f64) -> HyperDual {
+        HyperDual {
+            value: g,
+            d1: g_der * self.d1,
+            d2: g_der2 * self.d1 * self.d1 + g_der * self.d2,
+        }
+    }
+    pub fn is_constant(&self) -> bool {
+        self.d1 == 0.0 && self.d2 == 0.0
+    }
+    pub fn recip(self) -> HyperDual {
+        let u: f64 = self.value;
+        self.chain(1.0 / u, -1.0 / (u * u), 2.0 / (u * u * u))
+    }
+}
+
+impl Add for HyperDual {
+    type Output = HyperDual;
+    fn add(self, rhs: HyperDual) -> HyperDual {
+        HyperDual {
+            value: self.value + rhs.value,
+            d1: self.d1 + rhs.d1,
+            d2: self.d2 + rhs.d2,
+        }
+    }
+}
+
+impl Sub for HyperDual {
+    type Output = HyperDual;
+    fn sub(self, rhs: HyperDual) -> HyperDual {
+        HyperDual {
+            value: self.value - rhs.value,
+            d1: self.d1 - rhs.d1,
+            d2: self.d2 - rhs.d2,
+        }
+    }
+}
+
+impl Mul for HyperDual {
+    type Output = HyperDual;
+    fn mul(self, rhs: HyperDual) -> HyperDual {
+        HyperDual {
+            value: self.value * rhs.value,
+            d1: self.d1 * rhs.value + self.value * rhs.d1,
+            d2: self.d2 * rhs.value + 2.0 * self.d1 * rhs.d1 + self.value * rhs.d2,
+        }
+    }
+}
+
+impl Div for HyperDual {
+    type Output = HyperDual;
+    fn div(self, rhs: HyperDual) -> HyperDual {
+        if rhs.is_constant() {
+            return HyperDual {
+                value: self.value / rhs.value,
+                d1: self.d1 / rhs.value,
+                d2: self.d2 / rhs.value,
+            };
+        }
+        self * rhs.recip()
+    }
+}
+
+impl Neg for HyperDual {
+    type Output = HyperDual;
+    fn neg(self) -> HyperDual {
+        HyperDual {
+            value: -self.value,
+            d1: -self.d1,
+            d2: -self.d2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_and_quotient_rules() {
+        let x: HyperDual = HyperDual::variable(2.0);
+        // x³
+        let cube: HyperDual = x * x * x;
+        assert_eq!((cube.value, cube.d1, cube.d2), (8.0, 12.0, 12.0));
+        // 1/x
+        let inverse: HyperDual = HyperDual::constant(1.0) / x;
+        assert_eq!((inverse.value, inverse.d1, inverse.d2), (0.5, -0.25, 0.25));
+        // x/2 keeps the constant divisor out of the quotient rule
+        let half: HyperDual = x / HyperDual::constant(2.0);
+        assert_eq!((half.value, half.d1, half.d2), (1.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn chain_rule() {
+        // sin(x²) at x = 1.5
+        let x: f64 = 1.5;
+        let u: HyperDual = HyperDual::variable(x) * HyperDual::variable(x);
+        let g: HyperDual = u.chain(u.value.sin(), u.value.cos(), -u.value.sin());
+        let expected_d2: f64 = 2.0 * (x * x).cos() - 4.0 * x * x * (x * x).sin();
+        assert!((g.value - (x * x).sin()).abs() < 1e-12);
+        assert!((g.d1 - 2.0 * x * (x * x).cos()).abs() < 1e-12);
+        assert!((g.d2 - expected_d2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn constants_stay_constant() {
+        let c: HyperDual = -(HyperDual::constant(3.0) - HyperDual::constant(1.0));
+        assert!(c.is_constant());
+        assert_eq!(c.value, -2.0);
+        assert!(!HyperDual::variable(0.0).is_constant());
+    }
+}
@@ -0,0 +1,362 @@
+use crate::analysis::Analysis;
+use crate::expr::Expr;
+use crate::locale::Language;
+use crate::solver::{Iteration, Method};
+use crate::stop::{StopCriteria, StopRule};
+
+/// One step of the reasoning behind an iteration.
+pub struct Paragraph {
+    pub caption: String,
+    /// Formulas with the numbers put in, one per line.
+    pub formulas: Vec<String>,
+    pub conclusion: String,
+    /// The conclusion is that a condition of the method fails.
+    pub warning: bool,
+}
+
+/// Up to six significant digits, with a real minus sign.
+fn number(language: Language, value: f64) -> String {
+    let text: String = if value == 0.0 || !value.is_finite() {
+        value.to_string()
+    } else if (1e-4..1e6).contains(&value.abs()) {
+        let decimals: usize = (5.0 - value.abs().log10().floor()).max(0.0) as usize;
+        let fixed: String = format!("{:.1$}", value, decimals);
+        if fixed.contains('.') {
+            fixed
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        } else {
+            fixed
+        }
+    } else {
+        format!("{:.3e}", value)
+    };
+    language.decimal(text).replace('-', "−")
+}
+
+/// A number in brackets when it is negative, as a factor or a subtrahend.
+fn factor(language: Language, value: f64) -> String {
+    if value < 0.0 {
+        format!("({})", number(language, value))
+    } else {
+        number(language, value)
+    }
+}
+
+/// An approximation with enough digits to see the steps the tolerance is about.
+fn coordinate(language: Language, value: f64, eps: f64) -> String {
+    let decimals: usize = (-eps.log10()).ceil().max(0.0) as usize + 2;
+    language.fixed(value, decimals).replace('-', "−")
+}
+
+/// xₙ with `n` written as a subscript.
+pub fn x(n: usize) -> String {
+    let digits: String = n
+        .to_string()
+        .chars()
+        .map(|digit| {
+            "₀₁₂₃₄₅₆₇₈₉"
+                .chars()
+                .nth(digit as usize - '0' as usize)
+                .unwrap_or(digit)
+        })
+        .collect();
+    format!("x{}", digits)
+}
+
+/// How the method got to `iteration`. The chord method is written out formula by formula:
+/// the choice of the fixed endpoint for x₀, the chord formula for every next x.
+pub fn step(
+    language: Language,
+    expr: &Expr,
+    method: Method,
+    a: f64,
+    b: f64,
+    eps: f64,
+    iteration: &Iteration,
+) -> Vec<Paragraph> {
+    if method != Method::Chord {
+        return vec![Paragraph {
+            caption: language.fill("{} method", &[language.method(method)]),
+            formulas: vec![format!(
+                "{} = {}",
+                x(iteration.n),
+                number(language, iteration.x)
+            )],
+            conclusion: language.fill(
+                "The formulas are written out for the chord method; the {} method takes its step by its own rule.",
+                &[language.method(method)],
+            ),
+            warning: false,
+        }];
+    }
+    let coordinate = |value: f64| -> String { coordinate(language, value, eps) };
+    let Some(c) = iteration.fixed else {
+        return vec![];
+    };
+    if iteration.n == 0 {
+        let (fa, fb) = (expr.eval(a), expr.eval(b));
+        let (d2a, d2b) = (expr.eval_dual(a).d2, expr.eval_dual(b).d2);
+        return vec![
+            Paragraph {
+                caption: language.tr("Is there a root on [a, b]?").to_string(),
+                formulas: vec![format!(
+                    "f(a)·f(b) = f({})·f({}) = {}·{} = {}",
+                    number(language, a),
+                    number(language, b),
+                    factor(language, fa),
+                    factor(language, fb),
+                    number(language, fa * fb)
+                )],
+                conclusion: language
+                    .tr(if fa * fb < 0.0 {
+                        "f(a)·f(b) < 0, so [a, b] contains a root"
+                    } else {
+                        "f(a)·f(b) ≥ 0, so [a, b] need not contain a root"
+                    })
+                    .to_string(),
+                warning: fa * fb >= 0.0,
+            },
+            Paragraph {
+                caption: language.tr("Which endpoint is fixed?").to_string(),
+                formulas: vec![
+                    format!(
+                        "f(a)·f''(a) = {}·{} = {}",
+                        factor(language, fa),
+                        factor(language, d2a),
+                        number(language, fa * d2a)
+                    ),
+                    format!(
+                        "f(b)·f''(b) = {}·{} = {}",
+                        factor(language, fb),
+                        factor(language, d2b),
+                        number(language, fb * d2b)
+                    ),
+                ],
+                conclusion: language.fill(
+                    if c == a {
+                        "f(a)·f''(a) > 0, so the endpoint c = a = {} is fixed and x₀ = b = {}"
+                    } else {
+                        "f(a)·f''(a) ≤ 0, so the endpoint c = b = {} is fixed and x₀ = a = {}"
+                    },
+                    &[number(language, c), number(language, iteration.x)],
+                ),
+                warning: false,
+            },
+        ];
+    }
+    let (previous, next) = (x(iteration.n - 1), x(iteration.n));
+    let (fp, fc) = (expr.eval(iteration.x_prev), expr.eval(c));
+    let (d2, d2c) = (expr.eval_dual(iteration.x).d2, expr.eval_dual(c).d2);
+    let convex: bool = d2 * d2c >= 0.0;
+    vec![Paragraph {
+        caption: language
+            .tr("The next approximation is where the chord meets the x axis")
+            .to_string(),
+        formulas: vec![
+            format!(
+                "{n} = {p} − f({p})·({p} − c)/(f({p}) − f(c))",
+                n = next,
+                p = previous
+            ),
+            format!(
+                "{} = {} − {}·({} − {})/({} − {})",
+                next,
+                coordinate(iteration.x_prev),
+                factor(language, fp),
+                coordinate(iteration.x_prev),
+                if c < 0.0 {
+                    format!("({})", coordinate(c))
+                } else {
+                    coordinate(c)
+                },
+                number(language, fp),
+                factor(language, fc)
+            ),
+            format!("{} = {}", next, coordinate(iteration.x)),
+            format!("f({}) = {}", next, number(language, iteration.fx)),
+            format!(
+                "f''({})·f''(c) = {}·{} = {} {} 0",
+                next,
+                factor(language, d2),
+                factor(language, d2c),
+                number(language, d2 * d2c),
+                if convex { "≥" } else { "<" }
+            ),
+        ],
+        conclusion: language
+            .tr(if convex {
+                "The step is accepted: the new approximation is in [a, b] and f'' keeps its sign"
+            } else {
+                "Warning: f'' changes sign between c and the new approximation, so the convergence of the chord method is not guaranteed"
+            })
+            .to_string(),
+        warning: !convex,
+    }]
+}
+
+/// Each enabled stop rule compared with its tolerance, and whether the run stops here.
+pub fn stop_test(
+    language: Language,
+    stop: &StopCriteria,
+    method: Method,
+    analysis: &Analysis,
+    eps: f64,
+    iteration: &Iteration,
+) -> Paragraph {
+    let n: usize = iteration.n;
+    // Only the first iterate has no previous one, and then the step is NaN.
+    let previous: String = x(n.saturating_sub(1));
+    let step: f64 = (iteration.x - iteration.x_prev).abs();
+    let mut formulas: Vec<String> = vec![];
+    if stop.rules.len() > 1 {
+        formulas.push(
+            language
+                .tr(if stop.require_all {
+                    "All of the rules must hold:"
+                } else {
+                    "Any of the rules is enough:"
+                })
+                .to_string(),
+        );
+    }
+    for rule in stop.rules.iter() {
+        let holds: bool = stop.holds(*rule, method, iteration, analysis, eps);
+        let at_most: &str = if holds { "≤" } else { ">" };
+        let formula: String = match rule {
+            StopRule::Step | StopRule::Relative | StopRule::Bound if !step.is_finite() => {
+                language.fill("{}: there is no previous approximation yet", &[rule.name()])
+            }
+            StopRule::Step if method == Method::Chord => format!(
+                "|{} − {}| = {} {} ε = {}",
+                x(n),
+                previous,
+                number(language, step),
+                at_most,
+                number(language, eps)
+            ),
+            StopRule::Step => language.fill(
+                if holds {
+                    "{}: the test of the method holds"
+                } else {
+                    "{}: the test of the method does not hold"
+                },
+                &[rule.name()],
+            ),
+            StopRule::Bound => match analysis.error_estimate(method, iteration) {
+                Some(bound) if method == Method::Chord => format!(
+                    "|{n} − x*| ≤ (M₁ − m₁)/m₁·|{n} − {p}| = ({} − {})/{}·{} = {} {} ε = {}",
+                    number(language, analysis.d1.abs_max()),
+                    number(language, analysis.d1.abs_min()),
+                    number(language, analysis.d1.abs_min()),
+                    number(language, step),
+                    number(language, bound),
+                    at_most,
+                    number(language, eps),
+                    n = x(n),
+                    p = previous
+                ),
+                Some(bound) => format!(
+                    "|{} − x*| ≤ {} {} ε = {}",
+                    x(n),
+                    number(language, bound),
+                    at_most,
+                    number(language, eps)
+                ),
+                None => language.fill("{}: there is no estimate here", &[rule.name()]),
+            },
+            StopRule::Residual => format!(
+                "|f({})| = {} {} δ = {}",
+                x(n),
+                number(language, iteration.fx.abs()),
+                if holds { "<" } else { "≥" },
+                number(language, stop.delta)
+            ),
+            StopRule::Relative => format!(
+                "|{} − {}| = {} {} ε·|{}| = {}",
+                x(n),
+                previous,
+                number(language, step),
+                at_most,
+                x(n),
+                number(language, eps * iteration.x.abs())
+            ),
+        };
+        formulas.push(formula);
+    }
+    Paragraph {
+        caption: language.tr("Stopping test").to_string(),
+        formulas,
+        conclusion: if stop.stops(method, iteration, analysis, eps) {
+            language.fill(
+                "The stop rules are met, the answer is {} = {}",
+                &[x(n), coordinate(language, iteration.x, eps)],
+            )
+        } else {
+            language
+                .tr("The stop rules are not met, the iterations go on")
+                .to_string()
+        },
+        warning: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+
+    fn iteration(n: usize, x_prev: f64, x: f64, fixed: f64, f: &Expr) -> Iteration {
+        Iteration {
+            n,
+            x_prev,
+            x,
+            fx: f.eval(x),
+            fixed: Some(fixed),
+            bracket: None,
+            lines: vec![],
+            reached_eps: false,
+            evaluations: 1,
+        }
+    }
+
+    #[test]
+    fn sign_check_follows_the_sign() {
+        let f: Expr = expr::parse("x^2 - 2").unwrap();
+        let first: Iteration = iteration(0, f64::NAN, 1.0, 2.0, &f);
+        let paragraphs = step(Language::English, &f, Method::Chord, 1.0, 2.0, 1e-3, &first);
+        assert!(paragraphs[0].conclusion.starts_with("f(a)·f(b) < 0"));
+        assert!(!paragraphs[0].warning);
+        // (x − 1)² touches zero on [0, 2] without changing sign.
+        let f: Expr = expr::parse("(x - 1)^2").unwrap();
+        let first: Iteration = iteration(0, f64::NAN, 0.0, 2.0, &f);
+        let paragraphs = step(Language::English, &f, Method::Chord, 0.0, 2.0, 1e-3, &first);
+        assert!(paragraphs[0].conclusion.starts_with("f(a)·f(b) ≥ 0"));
+        assert!(paragraphs[0].warning);
+    }
+
+    #[test]
+    fn convexity_follows_the_sign() {
+        let f: Expr = expr::parse("x^3 - 0.5").unwrap();
+        // f'' = 6x has the sign of x.
+        let same: Iteration = iteration(2, 0.7, 0.75, 1.0, &f);
+        let paragraph: &Paragraph =
+            &step(Language::English, &f, Method::Chord, -1.0, 1.0, 1e-3, &same)[0];
+        assert!(paragraph.formulas[4].ends_with("≥ 0"));
+        assert!(!paragraph.warning);
+        let changed: Iteration = iteration(2, 0.1, -0.2, 1.0, &f);
+        let paragraph: &Paragraph = &step(
+            Language::English,
+            &f,
+            Method::Chord,
+            -1.0,
+            1.0,
+            1e-3,
+            &changed,
+        )[0];
+        assert!(paragraph.formulas[4].ends_with("< 0"));
+        assert!(paragraph.warning);
+        assert!(paragraph.conclusion.starts_with("Warning"));
+    }
+}
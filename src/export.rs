@@ -0,0 +1,417 @@
+use crate::solver::{Iteration, SolverError};
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Markdown,
+    Latex,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Csv, Format::Json, Format::Markdown, Format::Latex];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Csv => "CSV",
+            Format::Json => "JSON",
+            Format::Markdown => "Markdown",
+            Format::Latex => "LaTeX",
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Markdown => "md",
+            Format::Latex => "tex",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    Fixed,
+    Scientific,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: Format,
+    pub notation: Notation,
+    /// Digits after the decimal point.
+    pub precision: usize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> ExportOptions {
+        ExportOptions {
+            format: Format::Csv,
+            notation: Notation::Fixed,
+            precision: 6,
+        }
+    }
+}
+
+/// Everything about a run that goes into a report.
+pub struct Report<'a> {
+    pub title: &'a str,
+    pub method: &'a str,
+    pub a: f64,
+    pub b: f64,
+    pub eps: f64,
+    pub history: &'a [Iteration],
+    /// A-posteriori error estimate for each row of `history`.
+    pub estimates: Vec<Option<f64>>,
+    /// Whether the selected stop rules hold after each row of `history`.
+    pub stops: Vec<bool>,
+    pub error: Option<&'a SolverError>,
+}
+
+const HEADERS: [&str; 8] = [
+    "n",
+    "fixed",
+    "x_prev",
+    "x",
+    "f(x)",
+    "|x - x_prev|",
+    "estimate",
+    "stop",
+];
+
+impl ExportOptions {
+    /// An empty string for values that do not exist, like x₋₁ of the first row.
+    fn number(&self, value: Option<f64>) -> String {
+        match value {
+            Some(value) if !value.is_nan() => match self.notation {
+                Notation::Fixed => format!("{:.1$}", value, self.precision),
+                Notation::Scientific => format!("{:.1$e}", value, self.precision),
+            },
+            _ => String::new(),
+        }
+    }
+    fn json_number(&self, value: Option<f64>) -> serde_json::Value {
+        match self.number(value).parse::<f64>() {
+            Ok(rounded) if rounded.is_finite() => json!(rounded),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
+
+fn row(
+    options: &ExportOptions,
+    iteration: &Iteration,
+    estimate: Option<f64>,
+    stops: bool,
+) -> Vec<String> {
+    vec![
+        iteration.n.to_string(),
+        options.number(iteration.fixed),
+        options.number(Some(iteration.x_prev)),
+        options.number(Some(iteration.x)),
+        options.number(Some(iteration.fx)),
+        options.number(Some((iteration.x - iteration.x_prev).abs())),
+        options.number(estimate),
+        if stops { "yes" } else { "no" }.to_string(),
+    ]
+}
+
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn latex_escape(text: &str) -> String {
+    let mut escaped: String = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '^' => escaped.push_str("\\^{}"),
+            '~' => escaped.push_str("\\~{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The table rows of `report`, formatted with `options`.
+fn rows_of<'a>(
+    report: &'a Report,
+    options: &'a ExportOptions,
+) -> impl Iterator<Item = Vec<String>> + 'a {
+    report
+        .history
+        .iter()
+        .zip(report.estimates.iter().zip(report.stops.iter()))
+        .map(|(iteration, (estimate, stops))| row(options, iteration, *estimate, *stops))
+}
+
+/// A plain table with aligned columns, for a terminal.
+pub fn text(report: &Report, options: &ExportOptions) -> String {
+    let mut rows: Vec<Vec<String>> = vec![HEADERS.map(|header| header.to_string()).to_vec()];
+    rows.extend(rows_of(report, options));
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut text: String = format!(
+        "{} = 0, method: {}, a = {}, b = {}, eps = {}\n\n",
+        report.title, report.method, report.a, report.b, report.eps
+    );
+    for row in rows {
+        let fields: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(field, width)| format!("{:>1$}", field, width))
+            .collect();
+        text += fields.join("  ").trim_end();
+        text += "\n";
+    }
+    text
+}
+
+pub fn render(report: &Report, options: &ExportOptions) -> String {
+    let rows: Vec<Vec<String>> = rows_of(report, options).collect();
+    let result: String = match report.error {
+        Some(error) => format!("error: {}", error.title()),
+        None if report.stops.last().copied().unwrap_or(false) => "reached eps".to_string(),
+        None => "in progress".to_string(),
+    };
+    match options.format {
+        Format::Csv => {
+            let mut text: String = String::new();
+            for (key, value) in [
+                ("equation", format!("{} = 0", report.title)),
+                ("method", report.method.to_string()),
+                ("a", report.a.to_string()),
+                ("b", report.b.to_string()),
+                ("eps", report.eps.to_string()),
+                ("result", result),
+            ] {
+                text += &format!("{},{}\n", key, csv_field(&value));
+            }
+            text += "\n";
+            text += &HEADERS.map(csv_field).join(",");
+            text += "\n";
+            for row in rows {
+                text += &row
+                    .iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                text += "\n";
+            }
+            text
+        }
+        Format::Json => {
+            let iterations: Vec<serde_json::Value> = report
+                .history
+                .iter()
+                .zip(report.estimates.iter().zip(report.stops.iter()))
+                .map(|(iteration, (estimate, stops))| {
+                    json!({
+                        "n": iteration.n,
+                        "fixed": options.json_number(iteration.fixed),
+                        "x_prev": options.json_number(Some(iteration.x_prev)),
+                        "x": options.json_number(Some(iteration.x)),
+                        "fx": options.json_number(Some(iteration.fx)),
+                        "step": options.json_number(Some((iteration.x - iteration.x_prev).abs())),
+                        "estimate": options.json_number(*estimate),
+                        "stop": stops,
+                    })
+                })
+                .collect();
+            let document: serde_json::Value = json!({
+                "equation": format!("{} = 0", report.title),
+                "method": report.method,
+                "a": report.a,
+                "b": report.b,
+                "eps": report.eps,
+                "result": result,
+                "iterations": iterations,
+            });
+            serde_json::to_string_pretty(&document).unwrap_or_default() + "\n"
+        }
+        Format::Markdown => {
+            let mut text: String = format!("### {} = 0\n\n", report.title);
+            text += &format!(
+                "Method: {}, a = {}, b = {}, eps = {}, result: {}\n\n",
+                report.method, report.a, report.b, report.eps, result
+            );
+            let headers: Vec<String> = HEADERS.iter().map(|h| h.replace('|', "\\|")).collect();
+            text += &format!("| {} |\n", headers.join(" | "));
+            text += &format!("|{}\n", "---:|".repeat(HEADERS.len()));
+            for row in rows {
+                text += &format!("| {} |\n", row.join(" | "));
+            }
+            text
+        }
+        Format::Latex => {
+            let mut text: String = format!(
+                "\\begin{{tabular}}{{{}c}}\n\\hline\n",
+                "r".repeat(HEADERS.len() - 1)
+            );
+            text += &format!(
+                "\\multicolumn{{{}}}{{l}}{{{}}} \\\\\n",
+                HEADERS.len(),
+                latex_escape(&format!(
+                    "{} = 0; method: {}; a = {}, b = {}, eps = {}; {}",
+                    report.title, report.method, report.a, report.b, report.eps, result
+                ))
+            );
+            text += "\\hline\n";
+            text += "$n$ & fixed & $x_{n-1}$ & $x_n$ & $f(x_n)$ & $|x_n - x_{n-1}|$ & estimate & stop \\\\\n\\hline\n";
+            for row in rows {
+                text += &format!(
+                    "{} \\\\\n",
+                    row.iter()
+                        .map(|field| latex_escape(field))
+                        .collect::<Vec<_>>()
+                        .join(" & ")
+                );
+            }
+            text += "\\hline\n\\end{tabular}\n";
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iteration(n: usize, x_prev: f64, x: f64, fx: f64) -> Iteration {
+        Iteration {
+            n,
+            x_prev,
+            x,
+            fx,
+            fixed: Some(3.0),
+            bracket: None,
+            lines: vec![],
+            reached_eps: false,
+            evaluations: 1,
+        }
+    }
+
+    fn report<'a>(history: &'a [Iteration], stops: Vec<bool>) -> Report<'a> {
+        Report {
+            title: "x^2 - 4",
+            method: "Chord",
+            a: 1.0,
+            b: 3.0,
+            eps: 1e-9,
+            history,
+            estimates: vec![None; history.len()],
+            stops,
+            error: None,
+        }
+    }
+
+    fn options(format: Format) -> ExportOptions {
+        ExportOptions {
+            format,
+            ..ExportOptions::default()
+        }
+    }
+
+    #[test]
+    fn csv_quotes_only_what_needs_it() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        let history: [Iteration; 1] = [iteration(0, f64::NAN, 1.0, -3.0)];
+        let mut titled: Report = report(&history, vec![false]);
+        titled.title = "max(x, 1)";
+        assert!(render(&titled, &options(Format::Csv)).contains("equation,\"max(x, 1) = 0\"\n"));
+    }
+
+    #[test]
+    fn latex_escapes_special_characters() {
+        assert_eq!(
+            latex_escape("x^2 \\ 50% ~ a_b & {c} $ #"),
+            "x\\^{}2 \\textbackslash{} 50\\% \\~{} a\\_b \\& \\{c\\} \\$ \\#"
+        );
+        let history: [Iteration; 1] = [iteration(0, f64::NAN, 1.0, -3.0)];
+        let latex: String = render(&report(&history, vec![false]), &options(Format::Latex));
+        assert!(latex.contains("x\\^{}2 - 4 = 0"));
+        assert!(latex.ends_with("\\end{tabular}\n"));
+    }
+
+    #[test]
+    fn json_has_null_for_missing_values() {
+        let history: [Iteration; 2] = [
+            iteration(0, f64::NAN, 1.0, -3.0),
+            iteration(1, 1.0, 1.75, -0.9375),
+        ];
+        let json: serde_json::Value = serde_json::from_str(&render(
+            &report(&history, vec![false; 2]),
+            &options(Format::Json),
+        ))
+        .unwrap();
+        let first: &serde_json::Value = &json["iterations"][0];
+        assert!(first["x_prev"].is_null());
+        assert!(first["step"].is_null());
+        assert!(first["estimate"].is_null());
+        assert_eq!(json["iterations"][1]["step"], 0.75);
+    }
+
+    #[test]
+    fn precision_and_notation() {
+        let history: [Iteration; 1] = [iteration(1, 1.0, 1.75, -0.9375)];
+        let report: Report = report(&history, vec![false]);
+        let fixed: ExportOptions = ExportOptions {
+            precision: 2,
+            ..options(Format::Csv)
+        };
+        assert!(render(&report, &fixed).ends_with("1,3.00,1.00,1.75,-0.94,0.75,,no\n"));
+        let scientific: ExportOptions = ExportOptions {
+            notation: Notation::Scientific,
+            precision: 1,
+            ..options(Format::Markdown)
+        };
+        assert!(render(&report, &scientific).contains("| 1 | 3.0e0 | 1.0e0 | 1.8e0 | -9.4e-1 |"));
+        // JSON numbers are rounded to the same digits.
+        let json: serde_json::Value = serde_json::from_str(&render(
+            &report,
+            &ExportOptions {
+                precision: 1,
+                ..options(Format::Json)
+            },
+        ))
+        .unwrap();
+        assert_eq!(json["iterations"][0]["fx"], -0.9);
+    }
+
+    #[test]
+    fn stop_column_follows_the_stop_rules() {
+        // The solver's own step test never held, but a residual rule did.
+        let history: [Iteration; 2] = [
+            iteration(0, f64::NAN, 1.0, -3.0),
+            iteration(1, 1.0, 1.75, -0.9375),
+        ];
+        let stopped: Report = report(&history, vec![false, true]);
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&stopped, &options(Format::Json))).unwrap();
+        assert_eq!(json["result"], "reached eps");
+        assert_eq!(json["iterations"][0]["stop"], false);
+        assert_eq!(json["iterations"][1]["stop"], true);
+        let csv: String = render(&stopped, &options(Format::Csv));
+        assert!(csv.contains("result,reached eps\n"));
+        assert!(csv.ends_with(",yes\n"));
+        let running: Report = report(&history, vec![false, false]);
+        assert!(render(&running, &options(Format::Markdown)).contains("result: in progress"));
+    }
+}
@@ -0,0 +1,650 @@
+use crate::dual::HyperDual;
+use std::fmt;
+
+/// A parsed equation `f(x)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Variable,
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Func, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// Elementary functions that can be called inside an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Sqrt,
+    Abs,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Func> {
+        match name {
+            "sin" => Some(Func::Sin),
+            "cos" => Some(Func::Cos),
+            "tan" | "tg" => Some(Func::Tan),
+            "asin" | "arcsin" => Some(Func::Asin),
+            "acos" | "arccos" => Some(Func::Acos),
+            "atan" | "arctan" | "arctg" => Some(Func::Atan),
+            "sinh" | "sh" => Some(Func::Sinh),
+            "cosh" | "ch" => Some(Func::Cosh),
+            "tanh" | "th" => Some(Func::Tanh),
+            "exp" => Some(Func::Exp),
+            "ln" => Some(Func::Ln),
+            "lg" | "log" | "log10" => Some(Func::Log10),
+            "sqrt" => Some(Func::Sqrt),
+            "abs" => Some(Func::Abs),
+            _ => None,
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Func::Sin => "sin",
+            Func::Cos => "cos",
+            Func::Tan => "tan",
+            Func::Asin => "asin",
+            Func::Acos => "acos",
+            Func::Atan => "atan",
+            Func::Sinh => "sinh",
+            Func::Cosh => "cosh",
+            Func::Tanh => "tanh",
+            Func::Exp => "exp",
+            Func::Ln => "ln",
+            Func::Log10 => "lg",
+            Func::Sqrt => "sqrt",
+            Func::Abs => "abs",
+        }
+    }
+    pub fn apply(&self, x: f64) -> f64 {
+        match self {
+            Func::Sin => f64::sin(x),
+            Func::Cos => f64::cos(x),
+            Func::Tan => f64::tan(x),
+            Func::Asin => f64::asin(x),
+            Func::Acos => f64::acos(x),
+            Func::Atan => f64::atan(x),
+            Func::Sinh => f64::sinh(x),
+            Func::Cosh => f64::cosh(x),
+            Func::Tanh => f64::tanh(x),
+            Func::Exp => f64::exp(x),
+            Func::Ln => f64::ln(x),
+            Func::Log10 => f64::log10(x),
+            Func::Sqrt => f64::sqrt(x),
+            Func::Abs => f64::abs(x),
+        }
+    }
+    pub fn apply_dual(&self, u: HyperDual) -> HyperDual {
+        let x: f64 = u.value;
+        match self {
+            Func::Sin => u.chain(f64::sin(x), f64::cos(x), -f64::sin(x)),
+            Func::Cos => u.chain(f64::cos(x), -f64::sin(x), -f64::cos(x)),
+            Func::Tan => {
+                let t: f64 = f64::tan(x);
+                u.chain(t, 1.0 + t * t, 2.0 * t * (1.0 + t * t))
+            }
+            Func::Asin => {
+                let r: f64 = 1.0 - x * x;
+                u.chain(f64::asin(x), 1.0 / r.sqrt(), x / (r * r.sqrt()))
+            }
+            Func::Acos => {
+                let r: f64 = 1.0 - x * x;
+                u.chain(f64::acos(x), -1.0 / r.sqrt(), -x / (r * r.sqrt()))
+            }
+            Func::Atan => {
+                let r: f64 = 1.0 + x * x;
+                u.chain(f64::atan(x), 1.0 / r, -2.0 * x / (r * r))
+            }
+            Func::Sinh => u.chain(f64::sinh(x), f64::cosh(x), f64::sinh(x)),
+            Func::Cosh => u.chain(f64::cosh(x), f64::sinh(x), f64::cosh(x)),
+            Func::Tanh => {
+                let t: f64 = f64::tanh(x);
+                u.chain(t, 1.0 - t * t, -2.0 * t * (1.0 - t * t))
+            }
+            Func::Exp => {
+                let e: f64 = f64::exp(x);
+                u.chain(e, e, e)
+            }
+            Func::Ln => u.chain(f64::ln(x), 1.0 / x, -1.0 / (x * x)),
+            Func::Log10 => {
+                let ln10: f64 = std::f64::consts::LN_10;
+                u.chain(f64::log10(x), 1.0 / (x * ln10), -1.0 / (x * x * ln10))
+            }
+            Func::Sqrt => {
+                let s: f64 = f64::sqrt(x);
+                u.chain(s, 0.5 / s, -0.25 / (s * s * s))
+            }
+            Func::Abs => u.chain(f64::abs(x), f64::signum(x), 0.0),
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval(&self, x: f64) -> f64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Variable => x,
+            Expr::Neg(inner) => -inner.eval(x),
+            Expr::Binary(op, left, right) => {
+                let l: f64 = left.eval(x);
+                let r: f64 = right.eval(x);
+                match op {
+                    BinaryOp::Add => l + r,
+                    BinaryOp::Sub => l - r,
+                    BinaryOp::Mul => l * r,
+                    BinaryOp::Div => l / r,
+                    BinaryOp::Pow => pow(l, r),
+                }
+            }
+            Expr::Call(func, arg) => func.apply(arg.eval(x)),
+        }
+    }
+    /// Evaluates `f(x)`, `f'(x)` and `f''(x)` at once by forward-mode differentiation.
+    pub fn eval_dual(&self, x: f64) -> HyperDual {
+        match self {
+            Expr::Number(value) => HyperDual::constant(*value),
+            Expr::Variable => HyperDual::variable(x),
+            Expr::Neg(inner) => -inner.eval_dual(x),
+            Expr::Binary(op, left, right) => {
+                let l: HyperDual = left.eval_dual(x);
+                let r: HyperDual = right.eval_dual(x);
+                match op {
+                    BinaryOp::Add => l + r,
+                    BinaryOp::Sub => l - r,
+                    BinaryOp::Mul => l * r,
+                    BinaryOp::Div => l / r,
+                    BinaryOp::Pow => pow_dual(l, r),
+                }
+            }
+            Expr::Call(func, arg) => func.apply_dual(arg.eval_dual(x)),
+        }
+    }
+}
+
+/// A point where the automatic derivative disagrees with a finite-difference estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivativeMismatch {
+    pub x: f64,
+    pub order: u8,
+    pub automatic: f64,
+    pub numeric: f64,
+}
+
+impl Expr {
+    /// Compares `eval_dual` against central differences at `samples` points of `[a, b]`.
+    pub fn check_derivatives(&self, a: f64, b: f64, samples: usize) -> Option<DerivativeMismatch> {
+        for i in 0..=samples {
+            let x: f64 = a + (b - a) * i as f64 / samples as f64;
+            let dual: HyperDual = self.eval_dual(x);
+            if !(dual.value.is_finite() && dual.d1.is_finite() && dual.d2.is_finite()) {
+                continue;
+            }
+            let h1: f64 = 1e-6 * f64::max(1.0, x.abs());
+            let h2: f64 = 1e-4 * f64::max(1.0, x.abs());
+            let numeric_d1: f64 = (self.eval(x + h1) - self.eval(x - h1)) / (2.0 * h1);
+            let numeric_d2: f64 =
+                (self.eval(x + h2) - 2.0 * dual.value + self.eval(x - h2)) / (h2 * h2);
+            let scale: f64 = 1.0 + dual.value.abs();
+            for (order, automatic, numeric) in [(1, dual.d1, numeric_d1), (2, dual.d2, numeric_d2)]
+            {
+                if !numeric.is_finite() {
+                    continue;
+                }
+                if (automatic - numeric).abs() > 1e-3 * (scale + automatic.abs()) {
+                    return Some(DerivativeMismatch {
+                        x,
+                        order,
+                        automatic,
+                        numeric,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+fn pow_dual(base: HyperDual, exponent: HyperDual) -> HyperDual {
+    let u: f64 = base.value;
+    if exponent.is_constant() {
+        let c: f64 = exponent.value;
+        if c == 0.0 {
+            return HyperDual::constant(1.0);
+        }
+        if c == 1.0 {
+            return base;
+        }
+        return base.chain(
+            pow(u, c),
+            c * pow(u, c - 1.0),
+            c * (c - 1.0) * pow(u, c - 2.0),
+        );
+    }
+    if base.is_constant() {
+        let g: f64 = pow(u, exponent.value);
+        let ln: f64 = f64::ln(u);
+        return exponent.chain(g, g * ln, g * ln * ln);
+    }
+    // u^v = exp(v * ln(u))
+    Func::Exp.apply_dual(exponent * Func::Ln.apply_dual(base))
+}
+
+/// `powf` gives NaN for any negative base, even when the exponent is an integer.
+fn pow(base: f64, exponent: f64) -> f64 {
+    if exponent.fract() == 0.0 && exponent.abs() <= i32::MAX as f64 {
+        f64::powi(base, exponent as i32)
+    } else {
+        f64::powf(base, exponent)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // `inf` and `NaN` are not numbers to `parse`; these are, and give the same values.
+            Expr::Number(value) if value.is_nan() => write!(f, "(0 / 0)"),
+            Expr::Number(value) if value.is_infinite() && *value > 0.0 => write!(f, "1e999"),
+            Expr::Number(value) if value.is_infinite() => write!(f, "-(1e999)"),
+            Expr::Number(value) => write!(f, "{}", value),
+            Expr::Variable => write!(f, "x"),
+            Expr::Neg(inner) => write!(f, "-({})", inner),
+            Expr::Binary(op, left, right) => {
+                let sign: &str = match op {
+                    BinaryOp::Add => "+",
+                    BinaryOp::Sub => "-",
+                    BinaryOp::Mul => "*",
+                    BinaryOp::Div => "/",
+                    BinaryOp::Pow => "^",
+                };
+                write!(f, "({} {} {})", left, sign, right)
+            }
+            Expr::Call(func, arg) => write!(f, "{}({})", func.name(), arg),
+        }
+    }
+}
+
+/// Where and why the input could not be parsed. `position` counts characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    /// The message with `{}` where `detail` goes, so that it can be translated.
+    pub message: &'static str,
+    /// The offending text, empty when the message has no `{}`.
+    pub detail: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "position {}: {}",
+            self.position + 1,
+            self.message.replace("{}", &self.detail)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen,
+    End,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens: Vec<(Token, usize)> = vec![];
+    let mut i: usize = 0;
+    while i < chars.len() {
+        let c: char = chars[i];
+        let start: usize = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent part, e.g. 1e-3. A lone `e` is left for the constant.
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j: usize = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    while j < chars.len() && chars[j].is_ascii_digit() {
+                        j += 1;
+                    }
+                    i = j;
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<f64>() {
+                Ok(value) => tokens.push((Token::Number(value), start)),
+                Err(_) => {
+                    return Err(ParseError {
+                        position: start,
+                        message: "invalid number \"{}\"",
+                        detail: text,
+                    })
+                }
+            }
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            tokens.push((Token::Ident(text), start));
+            continue;
+        }
+        let token: Token = match c {
+            '+' => Token::Plus,
+            '-' | '−' => Token::Minus,
+            '*' | '·' | '×' => Token::Star,
+            '/' | ':' => Token::Slash,
+            '^' => Token::Caret,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            _ => {
+                return Err(ParseError {
+                    position: start,
+                    message: "unexpected character '{}'",
+                    detail: c.to_string(),
+                })
+            }
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    current: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current].0
+    }
+    fn position(&self) -> usize {
+        self.tokens[self.current].1
+    }
+    fn advance(&mut self) -> Token {
+        let token: Token = self.tokens[self.current].0.clone();
+        if self.current + 1 < self.tokens.len() {
+            self.current += 1;
+        }
+        token
+    }
+    fn error<T>(&self, message: &'static str, detail: &str) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.position(),
+            message,
+            detail: detail.to_string(),
+        })
+    }
+    // expression = term (("+" | "-") term)*
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.term()?;
+        loop {
+            let op: BinaryOp = match self.peek() {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.advance();
+            let right: Expr = self.term()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+    // term = unary (("*" | "/")? unary)*, a missing operator means multiplication: 5sin(x)
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.unary()?;
+        loop {
+            let op: BinaryOp = match self.peek() {
+                Token::Star => {
+                    self.advance();
+                    BinaryOp::Mul
+                }
+                Token::Slash => {
+                    self.advance();
+                    BinaryOp::Div
+                }
+                Token::Number(_) | Token::Ident(_) | Token::LParen => BinaryOp::Mul,
+                _ => return Ok(left),
+            };
+            let right: Expr = self.unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+    // unary = ("-" | "+") unary | power
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Token::Minus => {
+                self.advance();
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Token::Plus => {
+                self.advance();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+    // power = primary ("^" unary)?, right associative
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base: Expr = self.primary()?;
+        if *self.peek() == Token::Caret {
+            self.advance();
+            let exponent: Expr = self.unary()?;
+            return Ok(Expr::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+        Ok(base)
+    }
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let position: usize = self.position();
+        match self.advance() {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::LParen => {
+                let inner: Expr = self.expression()?;
+                if *self.peek() != Token::RParen {
+                    return self.error("expected ')'", "");
+                }
+                self.advance();
+                Ok(inner)
+            }
+            Token::Ident(name) => {
+                let lower: String = name.to_lowercase();
+                match lower.as_str() {
+                    "x" => return Ok(Expr::Variable),
+                    "pi" | "π" => return Ok(Expr::Number(std::f64::consts::PI)),
+                    "e" => return Ok(Expr::Number(std::f64::consts::E)),
+                    _ => {}
+                }
+                match Func::from_name(lower.as_str()) {
+                    Some(func) => {
+                        if *self.peek() != Token::LParen {
+                            return self.error("expected '(' after \"{}\"", &name);
+                        }
+                        self.advance();
+                        let arg: Expr = self.expression()?;
+                        if *self.peek() != Token::RParen {
+                            return self.error("expected ')'", "");
+                        }
+                        self.advance();
+                        Ok(Expr::Call(func, Box::new(arg)))
+                    }
+                    None => Err(ParseError {
+                        position,
+                        message: "unknown name \"{}\"",
+                        detail: name,
+                    }),
+                }
+            }
+            Token::End => Err(ParseError {
+                position,
+                message: "unexpected end of expression",
+                detail: String::new(),
+            }),
+            _ => Err(ParseError {
+                position,
+                message: "expected a number, x, a constant or a function",
+                detail: String::new(),
+            }),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let mut parser: Parser = Parser {
+        tokens: tokenize(input)?,
+        current: 0,
+    };
+    let expr: Expr = parser.expression()?;
+    if *parser.peek() != Token::End {
+        return parser.error("unexpected input after the end of expression", "");
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= 1e-9 * (1.0 + expected.abs())
+    }
+
+    #[test]
+    fn unary_minus_binds_looser_than_power() {
+        let expr: Expr = parse("-x^2").unwrap();
+        assert_eq!(expr.eval(3.0), -9.0);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(parse("2^3^2").unwrap().eval(0.0), 512.0);
+        assert_eq!(parse("2^-1").unwrap().eval(0.0), 0.5);
+    }
+
+    #[test]
+    fn implicit_multiplication() {
+        assert!(close(parse("5sin(x)").unwrap().eval(1.0), 5.0 * 1f64.sin()));
+        assert_eq!(parse("2x + 1").unwrap().eval(3.0), 7.0);
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        let error: ParseError = parse("x + foo(x)").unwrap_err();
+        assert_eq!(error.position, 4);
+        assert_eq!(error.detail, "foo");
+        assert!(parse("y").is_err());
+    }
+
+    #[test]
+    fn trailing_input_is_rejected() {
+        assert!(parse("x + 1)").is_err());
+        assert!(parse("x +").is_err());
+        assert!(parse("sin x").is_err());
+        assert!(parse("x $ 2").is_err());
+    }
+
+    #[test]
+    fn derivatives_match_closed_forms() {
+        let x: f64 = 0.7;
+        let cases: [(&str, [f64; 3]); 5] = [
+            ("sin(x)", [x.sin(), x.cos(), -x.sin()]),
+            (
+                "exp(2x)",
+                [
+                    (2.0 * x).exp(),
+                    2.0 * (2.0 * x).exp(),
+                    4.0 * (2.0 * x).exp(),
+                ],
+            ),
+            ("ln(x)", [x.ln(), 1.0 / x, -1.0 / (x * x)]),
+            ("x^3", [x.powi(3), 3.0 * x * x, 6.0 * x]),
+            (
+                "x^x",
+                [
+                    x.powf(x),
+                    x.powf(x) * (x.ln() + 1.0),
+                    x.powf(x) * ((x.ln() + 1.0).powi(2) + 1.0 / x),
+                ],
+            ),
+        ];
+        for (text, [value, d1, d2]) in cases {
+            let dual: HyperDual = parse(text).unwrap().eval_dual(x);
+            assert!(close(dual.value, value), "{}: f = {}", text, dual.value);
+            assert!(close(dual.d1, d1), "{}: f' = {}", text, dual.d1);
+            assert!(close(dual.d2, d2), "{}: f'' = {}", text, dual.d2);
+        }
+    }
+
+    #[test]
+    fn derivative_check_agrees_with_differences() {
+        let expr: Expr = parse("x^2 - 5*sin(x) + exp(-x/2)").unwrap();
+        assert_eq!(expr.check_derivatives(1.0, 3.0, 20), None);
+    }
+
+    #[test]
+    fn display_parses_back() {
+        for text in [
+            "x^2 - 5*sin(x)",
+            "-x^2 + 2^3^2",
+            "ln(x) / (1 - x) * sqrt(abs(x))",
+            "0.1 * x - 1e-7",
+            "1e999 * x",
+        ] {
+            let expr: Expr = parse(text).unwrap();
+            assert_eq!(parse(&expr.to_string()), Ok(expr), "{}", text);
+        }
+        let expr: Expr = Expr::Binary(
+            BinaryOp::Add,
+            Box::new(Expr::Number(f64::NEG_INFINITY)),
+            Box::new(Expr::Variable),
+        );
+        assert_eq!(
+            parse(&expr.to_string()).unwrap().eval(1.0),
+            f64::NEG_INFINITY
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use crate::solver::{Method, SolverError};
+use eframe::egui;
+
+/// The languages of the interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Russian,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Russian];
+    /// The name of the language in the language itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Russian => "Русский",
+        }
+    }
+    pub fn key(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Russian => "ru",
+        }
+    }
+    pub fn from_key(key: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.key() == key)
+    }
+    /// Russian when the system locale is Russian, English otherwise.
+    pub fn from_environment() -> Language {
+        let russian: bool = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .is_some_and(|value| value.starts_with("ru"));
+        if russian {
+            Language::Russian
+        } else {
+            Language::English
+        }
+    }
+    /// `text` in this language. The English text is the key of the catalog,
+    /// so a message without a translation stays in English.
+    pub fn tr(&self, text: &'static str) -> &'static str {
+        match self {
+            Language::English => text,
+            Language::Russian => russian(text).unwrap_or(text),
+        }
+    }
+    /// The translated `text` with its `{}` replaced by `args` in order.
+    pub fn fill(&self, text: &'static str, args: &[impl AsRef<str>]) -> String {
+        let mut parts = self.tr(text).split("{}");
+        let mut filled: String = parts.next().unwrap_or_default().to_string();
+        for (i, part) in parts.enumerate() {
+            filled += args.get(i).map_or("", AsRef::as_ref);
+            filled += part;
+        }
+        filled
+    }
+    pub fn method(&self, method: Method) -> &'static str {
+        match self {
+            Language::English => method.name(),
+            Language::Russian => match method {
+                Method::Chord => "Хорд",
+                Method::Bisection => "Бисекции",
+                Method::Newton => "Ньютона",
+                Method::Secant => "Секущих",
+                Method::RegulaFalsi => "Ложного положения",
+                Method::Illinois => "Иллинойс",
+                Method::Ridders => "Риддерса",
+                Method::Brent => "Брента",
+                Method::ChordTangent => "Хорд и касательных",
+            },
+        }
+    }
+    /// A formatted number with the decimal separator of this language.
+    pub fn decimal(&self, number: String) -> String {
+        match self {
+            Language::English => number,
+            Language::Russian => number.replace('.', ","),
+        }
+    }
+    pub fn fixed(&self, value: f64, decimals: usize) -> String {
+        self.decimal(format!("{:.1$}", value, decimals))
+    }
+    pub fn scientific(&self, value: f64, decimals: usize) -> String {
+        self.decimal(format!("{:.1$e}", value, decimals))
+    }
+    /// The shortest text that reads back as `value`.
+    pub fn plain(&self, value: f64) -> String {
+        self.decimal(value.to_string())
+    }
+    /// Shows the number of `drag` with the decimal separator of this language.
+    /// Both separators are accepted when a number is typed in.
+    pub fn drag_value<'a>(&self, drag: egui::DragValue<'a>) -> egui::DragValue<'a> {
+        let language: Language = *self;
+        drag.custom_formatter(move |value, decimals| {
+            language.decimal(egui::emath::format_with_decimals_in_range(value, decimals))
+        })
+        .custom_parser(|text| text.trim().replace(',', ".").parse::<f64>().ok())
+    }
+    /// Why the solver stopped, in this language.
+    pub fn error(&self, error: &SolverError) -> String {
+        if *self == Language::English {
+            return error.to_string();
+        }
+        let n = |value: f64| -> String { self.plain(value) };
+        match *error {
+            SolverError::NoSignChange { a, b, fa, fb } => format!(
+                "f(a) = f({}) = {} и f(b) = f({}) = {} одного знака. \
+                 Непрерывная функция заведомо имеет корень на [a; b], \
+                 только если f(a)·f(b) < 0, поэтому выберите более узкий или другой отрезок.",
+                n(a),
+                n(fa),
+                n(b),
+                n(fb)
+            ),
+            SolverError::OutOfInterval { x, a, b } => format!(
+                "Следующее приближение x = {} лежит вне [{}; {}]. \
+                 Так бывает, когда на отрезке не выполнены условия сходимости: \
+                 f' или f'' меняют знак, или корень не лежит между a и b.",
+                n(x),
+                n(a),
+                n(b)
+            ),
+            SolverError::ConvexityChanged {
+                x,
+                d2,
+                fixed,
+                d2_fixed,
+            } => format!(
+                "f''({}) = {}, но f''({}) = {} в неподвижном конце. \
+                 Методу хорд нужно, чтобы f'' сохраняла знак на [a; b], \
+                 иначе хорды могут перескочить через корень. Сузьте отрезок.",
+                n(x),
+                n(d2),
+                n(fixed),
+                n(d2_fixed)
+            ),
+            SolverError::FlatChord {
+                x1,
+                other,
+                denominator,
+            } => format!(
+                "f({}) − f({}) = {} почти равно нулю, поэтому хорда через эти точки \
+                 почти горизонтальна и не пересекает ось x рядом с корнем.",
+                n(x1),
+                n(other),
+                n(denominator)
+            ),
+            SolverError::FlatTangent { x, derivative } => format!(
+                "f'({}) = {} почти равна нулю, поэтому касательная почти горизонтальна \
+                 и пересекает ось x далеко от корня.",
+                n(x),
+                n(derivative)
+            ),
+            SolverError::Undefined { x } => format!(
+                "f({}) не является конечным числом. Точка лежит вне области определения \
+                 функции (например, ln(x) при x ≤ 0 или деление на ноль). \
+                 Выберите отрезок, на котором f определена.",
+                n(x)
+            ),
+            SolverError::IterationLimit { iterations, x } => format!(
+                "Точность не достигнута за {} итераций, последнее приближение x = {}. \
+                 Метод сходится на этом отрезке слишком медленно или не сходится вовсе.",
+                iterations,
+                n(x)
+            ),
+            SolverError::TimeLimit {
+                seconds,
+                iterations,
+                x,
+            } => format!(
+                "Точность не достигнута за {} с ({} итераций), \
+                 последнее приближение x = {}. Увеличьте лимит времени или проверьте правила остановки.",
+                n(seconds),
+                iterations,
+                n(x)
+            ),
+        }
+    }
+}
+
+/// egui's own fonts with fallbacks from the system for the symbols they lack.
+/// The bundled Ubuntu and Hack fonts cover Cyrillic; subscripts such as xₙ₋₁,
+/// ∪, ∈, ✓ and ✗ are only drawn when one of the fallbacks is installed.
+pub fn fonts() -> egui::FontDefinitions {
+    const FALLBACKS: [&str; 6] = [
+        "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+        "/usr/share/fonts/TTF/DejaVuSans.ttf",
+        "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+        "C:\\Windows\\Fonts\\seguisym.ttf",
+        "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+        "/Library/Fonts/Arial Unicode.ttf",
+    ];
+    let mut fonts: egui::FontDefinitions = egui::FontDefinitions::default();
+    for path in FALLBACKS {
+        let Ok(data) = std::fs::read(path) else {
+            continue;
+        };
+        fonts
+            .font_data
+            .insert(path.to_string(), egui::FontData::from_owned(data));
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            fonts
+                .families
+                .entry(family)
+                .or_default()
+                .push(path.to_string());
+        }
+    }
+    fonts
+}
+
+/// The Russian catalog.
+fn russian(text: &str) -> Option<&'static str> {
+    Some(match text {
+        // Top panel.
+        "Chord method Showcase." => "Демонстрация метода хорд.",
+        "Source code:" => "Исходный код:",
+        "HELP" => "СПРАВКА",
+        "Help" => "Справка",
+        "Session" => "Сессия",
+        "Compare" => "Сравнение",
+        "Export plot" => "Экспорт графика",
+        "Export" => "Экспорт",
+        "History" => "История",
+        "Theme: {}" => "Тема: {}",
+        "Language: {}" => "Язык: {}",
+        "Author:" => "Автор:",
+        // Bottom panel.
+        "x = {}, f(x) = {}, root in [{}; {}]" => "x = {}; f(x) = {}; корень в [{}; {}]",
+        "x = {}, f(x) = {}" => "x = {}; f(x) = {}",
+        "Iteration {}" => "Итерация {}",
+        "Error: {}" => "Ошибка: {}",
+        "Reached end!" => "Точность достигнута!",
+        "Stopped by {}" => "Остановлено по {}",
+        " and " => " и ",
+        "Error bound: |xₙ − x*| ≤ {}" => "Оценка погрешности: |xₙ − x*| ≤ {}",
+        // Conditions.
+        "Domain of f: {}" => "Область определения f: {}",
+        "Expected roots: {}" => "Ожидаемые корни: {}",
+        "|x − x*| = {} to the nearest one" => "|x − x*| = {} до ближайшего",
+        "Conditions on [a, b]" => "Условия на [a; b]",
+        "Sign change: f(a)·f(b) < 0" => "Смена знака: f(a)·f(b) < 0",
+        "f is defined on [a, b]" => "f определена на [a; b]",
+        "Monotonic: f' keeps its sign" => "Монотонность: f' сохраняет знак",
+        "Constant convexity: f'' keeps its sign" => "Постоянная выпуклость: f'' сохраняет знак",
+        "m₁ = min|f'| = {}, M₁ = max|f'| = {}" => "m₁ = min|f'| = {}; M₁ = max|f'| = {}",
+        // Equation and data.
+        "Choose your equation ({}): " => "Выберите уравнение ({}): ",
+        "Choose your method: " => "Выберите метод: ",
+        "Add equation:" => "Добавить уравнение:",
+        "Add" => "Добавить",
+        "Parse error at position {}: {}" => "Ошибка разбора в позиции {}: {}",
+        "invalid number \"{}\"" => "неверное число «{}»",
+        "unexpected character '{}'" => "неожиданный символ «{}»",
+        "expected ')'" => "ожидается «)»",
+        "expected '(' after \"{}\"" => "ожидается «(» после «{}»",
+        "unknown name \"{}\"" => "неизвестное имя «{}»",
+        "unexpected end of expression" => "неожиданный конец выражения",
+        "expected a number, x, a constant or a function" => {
+            "ожидается число, x, константа или функция"
+        }
+        "unexpected input after the end of expression" => "лишние символы после конца выражения",
+        "Set data:" => "Данные:",
+        "Apply data:" => "Применить данные:",
+        "Update" => "Обновить",
+        "Stop when:" => "Остановка, когда:",
+        "any of them" => "любое из них",
+        "all of them" => "все сразу",
+        "Initial approximation:" => "Начальное приближение:",
+        "from: " => "от: ",
+        "to: " => "до: ",
+        "Find roots" => "Найти корни",
+        "{} of {} brackets" => "отрезков: {} из {}",
+        "Cancelled after {} of {} brackets, the others are not solved" => {
+            "Отменено после {} из {} отрезков, остальные не решены"
+        }
+        "Find all roots" => "Найти все корни",
+        "Clear roots" => "Очистить корни",
+        "Click to use:" => "Нажмите, чтобы выбрать:",
+        "[{}; {}] even root?" => "[{}; {}] корень чётной кратности?",
+        "f changes sign on this interval" => "f меняет знак на этом отрезке",
+        "|f| almost touches zero here without changing sign" => {
+            "|f| почти касается нуля, не меняя знака"
+        }
+        "a: {}, b: {}, eps: {}" => "a: {}; b: {}; eps: {}",
+        "Warning: f{} at x = {} is {} but finite differences give {}" => {
+            "Внимание: f{} в x = {} равна {}, но конечные разности дают {}"
+        }
+        // Convergence and playback.
+        "Convergence:" => "Сходимость:",
+        "empirical order p ≈ {}, rate constant C ≈ {} (eₙ₊₁ ≈ C·eₙᵖ)" => {
+            "эмпирический порядок p ≈ {}; константа C ≈ {} (eₙ₊₁ ≈ C·eₙᵖ)"
+        }
+        "empirical order p ≈ {}" => "эмпирический порядок p ≈ {}",
+        "the order needs three steps |xₙ − xₙ₋₁|" => "для порядка нужны три шага |xₙ − xₙ₋₁|",
+        "timeline" => "шкала итераций",
+        "The saved run is reproduced up to position {}; branch there to continue" => {
+            "Сохранённый расчёт воспроизводится до позиции {}; чтобы продолжить, начните ветку оттуда"
+        }
+        "Branch from here" => "Продолжить отсюда",
+        "Drops the later iterations and continues from this one with eps from \"Set data\"" => {
+            "Отбрасывает следующие итерации и продолжает с этой с eps из «Данные»"
+        }
+        "Previous iteration" => "Предыдущая итерация",
+        "⏸ Pause" => "⏸ Пауза",
+        "▶ Play" => "▶ Воспроизвести",
+        "Next iteration" => "Следующая итерация",
+        "Cancel" => "Отмена",
+        "{} iterations, {} s" => "итераций: {}; {} с",
+        "Solve" => "Решить",
+        " s" => " с",
+        "delay" => "задержка",
+        "max iterations: " => "макс. итераций: ",
+        "time budget: " => "лимит времени: ",
+        // Explanations.
+        "Explain" => "Объяснение",
+        "Make a step to see it explained" => "Сделайте шаг, чтобы увидеть объяснение",
+        "The next step failed: {}" => "Следующий шаг не удался: {}",
+        "{} method" => "Метод {}",
+        "The formulas are written out for the chord method; the {} method takes its step by its own rule." => {
+            "Формулы расписываются для метода хорд; метод {} делает шаг по своему правилу."
+        }
+        "Is there a root on [a, b]?" => "Есть ли корень на [a; b]?",
+        "f(a)·f(b) < 0, so [a, b] contains a root" => "f(a)·f(b) < 0, значит, на [a; b] есть корень",
+        "f(a)·f(b) ≥ 0, so [a, b] need not contain a root" => {
+            "f(a)·f(b) ≥ 0, значит, на [a; b] корня может не быть"
+        }
+        "Which endpoint is fixed?" => "Какой конец неподвижен?",
+        "f(a)·f''(a) > 0, so the endpoint c = a = {} is fixed and x₀ = b = {}" => {
+            "f(a)·f''(a) > 0, поэтому неподвижен конец c = a = {}, а x₀ = b = {}"
+        }
+        "f(a)·f''(a) ≤ 0, so the endpoint c = b = {} is fixed and x₀ = a = {}" => {
+            "f(a)·f''(a) ≤ 0, поэтому неподвижен конец c = b = {}, а x₀ = a = {}"
+        }
+        "The next approximation is where the chord meets the x axis" => {
+            "Следующее приближение — точка пересечения хорды с осью x"
+        }
+        "The step is accepted: the new approximation is in [a, b] and f'' keeps its sign" => {
+            "Шаг принят: новое приближение лежит в [a; b], и f'' сохраняет знак"
+        }
+        "Warning: f'' changes sign between c and the new approximation, so the convergence of the chord method is not guaranteed" => {
+            "Внимание: f'' меняет знак между c и новым приближением, поэтому сходимость метода хорд не гарантирована"
+        }
+        "Stopping test" => "Проверка остановки",
+        "All of the rules must hold:" => "Должны выполниться все правила:",
+        "Any of the rules is enough:" => "Достаточно любого правила:",
+        "{}: there is no previous approximation yet" => "{}: предыдущего приближения ещё нет",
+        "{}: the test of the method holds" => "{}: проверка метода выполнена",
+        "{}: the test of the method does not hold" => "{}: проверка метода не выполнена",
+        "{}: there is no estimate here" => "{}: оценки здесь нет",
+        "The stop rules are met, the answer is {} = {}" => {
+            "Условия остановки выполнены, ответ {} = {}"
+        }
+        "The stop rules are not met, the iterations go on" => {
+            "Условия остановки не выполнены, итерации продолжаются"
+        }
+        // Quiz.
+        "Quiz" => "Самопроверка",
+        "Name:" => "Имя:",
+        "What is {}? Type it in or click on the plot where the chord crosses the x axis." => {
+            "Чему равно {}? Введите значение или щёлкните на графике там, где хорда пересекает ось x."
+        }
+        "accept within ±" => "допуск ±",
+        "Check" => "Проверить",
+        "✓ Correct" => "✓ Верно",
+        "✗ Wrong" => "✗ Неверно",
+        "{} = {}, your answer {}, error {}" => "{} = {}, ваш ответ {}, погрешность {}",
+        "Score: {}/{}" => "Счёт: {}/{}",
+        "Equation" => "Уравнение",
+        "Your answer" => "Ваш ответ",
+        "Error" => "Погрешность",
+        "Reset score" => "Сбросить счёт",
+        // Tables.
+        "All roots" => "Все корни",
+        "{} = 0 on [{}; {}], method: {}, eps: {}" => "{} = 0 на [{}; {}]; метод: {}; eps: {}",
+        "Iterations" => "Итерации",
+        "Result" => "Результат",
+        "Iteration history" => "История итераций",
+        "Fixed" => "Неподвижный конец",
+        "Order p" => "Порядок p",
+        "Error estimate" => "Оценка погрешности",
+        "Stop" => "Остановка",
+        // Export.
+        "Format:" => "Формат:",
+        "Numbers:" => "Числа:",
+        "digits: " => "знаков: ",
+        "File:" => "Файл:",
+        "Save" => "Сохранить",
+        "Copy to clipboard" => "Копировать в буфер обмена",
+        "Saved to {}" => "Сохранено в {}",
+        "Could not save: {}" => "Не удалось сохранить: {}",
+        "Copied to clipboard" => "Скопировано в буфер обмена",
+        // Session and problem sets.
+        "Equations, a, b, eps, methods, stop rules, iterations, the theme and the language." => {
+            "Уравнения, a, b, eps, методы, правила остановки, итерации, тема и язык."
+        }
+        "Save session as…" => "Сохранить сессию как…",
+        "Open session…" => "Открыть сессию…",
+        "Could not open: {}" => "Не удалось открыть: {}",
+        "Opened {}" => "Открыт файл {}",
+        "The last session could not be restored: {}" => {
+            "Не удалось восстановить прошлую сессию: {}"
+        }
+        "Problem set: {}" => "Набор задач: {}",
+        "Load problem set" => "Загрузить набор задач",
+        "Replaces the equations of the current set, typed-in ones stay" => {
+            "Заменяет уравнения текущего набора, введённые вручную остаются"
+        }
+        "Could not load: {}" => "Не удалось загрузить: {}",
+        "Loaded {}" => "Загружен набор {}",
+        // Comparison.
+        "Compare methods" => "Сравнение методов",
+        "Methods:" => "Методы:",
+        "Start" => "Начать",
+        "Runs the ticked methods on the current equation, a, b and eps" => {
+            "Запускает отмеченные методы на текущем уравнении с его a, b и eps"
+        }
+        "{} = 0 on [{}; {}], eps: {}" => "{} = 0 на [{}; {}]; eps: {}",
+        "Method" => "Метод",
+        "f evaluations" => "Вычислений f",
+        "Time" => "Время",
+        "x* is found by Brent's method with eps = 1e-15" => {
+            "x* найден методом Брента с eps = 1e-15"
+        }
+        "{} µs" => "{} мкс",
+        // Plot export.
+        "Colors:" => "Цвета:",
+        "Current theme" => "Текущая тема",
+        "Print (white)" => "Для печати (белый фон)",
+        "Size:" => "Размер:",
+        " px" => " пикс.",
+        "PNG scale: " => "Масштаб PNG: ",
+        "Visible range: x ∈ [{}; {}], y ∈ [{}; {}]" => "Видимая область: x ∈ [{}; {}]; y ∈ [{}; {}]",
+        "Save SVG" => "Сохранить SVG",
+        "Save PNG" => "Сохранить PNG",
+        // Plot legend.
+        "Domain" => "Область определения",
+        "Bracket" => "Отрезок с корнем",
+        "f' changes sign" => "f' меняет знак",
+        "Root #{}" => "Корень №{}",
+        "Chord" => "Хорда",
+        "Tangent" => "Касательная",
+        "Showcase" => "Построение",
+        "Selected iteration" => "Выбранная итерация",
+        "Next x" => "Следующий x",
+        "Expected root" => "Ожидаемый корень",
+        "Left and Right borders" => "Левая и правая границы",
+        // Solver errors.
+        "No sign change on [a, b]" => "Нет смены знака на [a; b]",
+        "The iterate left the interval" => "Приближение вышло за отрезок",
+        "f'' changes sign" => "f'' меняет знак",
+        "Division by a near-zero chord slope" => "Деление на почти нулевой наклон хорды",
+        "Division by a near-zero derivative" => "Деление на почти нулевую производную",
+        "f is undefined" => "f не определена",
+        "Iteration limit reached" => "Достигнут предел итераций",
+        "Time budget exceeded" => "Превышен лимит времени",
+        // Help.
+        "Running the solver" => "Решение",
+        "Equations and domains" => "Уравнения и области определения",
+        "Comparing methods" => "Сравнение методов",
+        "Explanations and quizzes" => "Объяснения и самопроверка",
+        "Sessions, files and language" => "Сессии, файлы и язык",
+        "This program solves a nonlinear equation using the chord method." => {
+            "Программа решает нелинейное уравнение методом хорд."
+        }
+        "You can choose the equation by selecting it in a \"Choose your equation\" menu and set your data below." => {
+            "Выберите уравнение в списке «Выберите уравнение» и задайте данные ниже."
+        }
+        "Then you can walk through iteration by clicking the \"Next iteration\" button or the \"Solve\" button." => {
+            "Затем проходите итерации кнопкой «Следующая итерация» или нажмите «Решить»."
+        }
+        "Each iteration will show on the graph how it finds each x closer to a real one." => {
+            "Каждая итерация показывает на графике, как очередной x приближается к корню."
+        }
+        "\"Stop when\" picks the stop rules: the step, the rigorous bound (M₁ − m₁)/m₁·|xₙ − xₙ₋₁| for the chord method, |f(x)| < δ or a relative step." => {
+            "«Остановка, когда» выбирает правила остановки: шаг, строгую оценку (M₁ − m₁)/m₁·|xₙ − xₙ₋₁| для метода хорд, |f(x)| < δ или относительный шаг."
+        }
+        "\"Solve\" runs in the background up to the iteration cap and the time budget; \"Cancel\" keeps the iterations found so far." => {
+            "«Решить» считает в фоне до предела итераций и лимита времени; «Отмена» сохраняет уже найденные итерации."
+        }
+        "\"Play\" steps on its own with the chosen delay, \"Pause\" stops it." => {
+            "«Воспроизвести» делает шаги сама с выбранной задержкой, «Пауза» останавливает."
+        }
+        "\"Previous iteration\" and the timeline go back to any step; \"Branch from here\" continues from it with a new eps." => {
+            "«Предыдущая итерация» и шкала итераций возвращают к любому шагу; «Продолжить отсюда» продолжает с него с новым eps."
+        }
+        "You can also type your own equation in the \"Add equation\" field, for example x^3 - 2*sin(x) + exp(-x/2)." => {
+            "Можно ввести своё уравнение в поле «Добавить уравнение», например x^3 - 2*sin(x) + exp(-x/2)."
+        }
+        "Supported: + - * / ^, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, exp, ln, lg, sqrt, abs and the constants pi and e." => {
+            "Поддерживаются + - * / ^, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, exp, ln, lg, sqrt, abs и константы pi и e."
+        }
+        "Each equation has a domain, shaded on the graph; a and b are kept inside it, and the domain of a typed-in equation is found from ln, sqrt, division and powers." => {
+            "У каждого уравнения есть область определения, она закрашена на графике; a и b не выходят за неё, а для введённого уравнения она находится по ln, sqrt, делению и степеням."
+        }
+        "\"Compare\" runs two to four methods side by side on the same a, b and eps, steps them together and sums up iterations, function evaluations, errors and time." => {
+            "«Сравнение» запускает от двух до четырёх методов рядом с одинаковыми a, b и eps, делает шаги одновременно и сводит итерации, вычисления функции, погрешности и время."
+        }
+        "Without the window: opr solve --equation \"x^2 - 5*sin(x)\" --a 1.5 --b 3 --eps 1e-6 --method chord [--json], see opr solve --help." => {
+            "Без окна: opr solve --equation \"x^2 - 5*sin(x)\" --a 1.5 --b 3 --eps 1e-6 --method chord [--json], см. opr solve --help."
+        }
+        "The session is saved on exit and restored on launch; \"Session\" saves it to a file or opens one, for example a prepared scenario." => {
+            "Сессия сохраняется при выходе и восстанавливается при запуске; «Сессия» сохраняет её в файл или открывает файл, например подготовленный сценарий."
+        }
+        "The equations come from a problem set, a JSON file with titles, domains, brackets, eps, allowed methods and expected roots; \"Session\" loads another one." => {
+            "Уравнения берутся из набора задач — JSON-файла с названиями, областями определения, отрезками, eps, допустимыми методами и ожидаемыми корнями; другой набор загружается в «Сессии»."
+        }
+        "\"Quiz\" hides the next approximation until you type it in or click where the chord crosses the axis, then \"Check\" shows it with your error and the score; the results save as CSV for the teacher." => {
+            "«Самопроверка» скрывает следующее приближение, пока вы не введёте его или не щёлкнете там, где хорда пересекает ось; «Проверить» показывает его с вашей погрешностью и счётом, результаты сохраняются в CSV для преподавателя."
+        }
+        "\"Explain\" writes out the shown iteration: the sign check, the choice of the fixed endpoint, the chord formula with the numbers put in and the stopping test; a row picked in \"History\" is explained instead." => {
+            "«Объяснение» расписывает показанную итерацию: проверку смены знака, выбор неподвижного конца, формулу хорд с подставленными числами и проверку остановки; если выбрать строку в «Истории», объясняется она."
+        }
+        "The language button switches between English and Russian; Russian numbers use a decimal comma, exported files keep the point." => {
+            "Кнопка языка переключает английский и русский; в русском числа пишутся с десятичной запятой, в экспортируемых файлах остаётся точка."
+        }
+        "\"Export plot\" saves the visible part of the graph as SVG or PNG, in the current theme or in print colors." => {
+            "«Экспорт графика» сохраняет видимую часть графика в SVG или PNG в текущей теме или в цветах для печати."
+        }
+        _ => return None,
+    })
+}
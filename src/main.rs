@@ -1,8 +1,39 @@
-use crate::colors::{set_theme, Theme, FRAPPE, LATTE, MACCHIATO, MOCHA};
+use crate::analysis::{Analysis, Bracket, BracketKind, Condition, Convergence};
+use crate::colors::{set_theme, Theme, FRAPPE, LATTE, MACCHIATO, MOCHA, THEMES};
+use crate::domain::Domain;
+use crate::export::{ExportOptions, Format, Notation, Report};
+use crate::expr::{DerivativeMismatch, Expr, ParseError};
+use crate::locale::Language;
+use crate::problems::{Problem, ProblemSet};
+use crate::quiz::Quiz;
+use crate::scene::Scene;
+use crate::session::{SavedFunction, Session};
+use crate::solver::{Iteration, LineKind, Method, RootSolver, Run, SolverError};
+use crate::stop::{StopCriteria, StopRule};
+use crate::worker::{Job, Message, Scan, ScanJob, Worker};
 use eframe::egui;
 use std::collections::HashMap;
+mod analysis;
+mod cli;
 mod colors;
+mod domain;
+mod dual;
+mod explain;
+mod export;
+mod expr;
+mod locale;
+mod problems;
+mod quiz;
+mod scene;
+mod session;
+mod solver;
+mod stop;
+mod worker;
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|command| command == "solve") {
+        std::process::exit(cli::solve(&args[2..]));
+    }
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "MathApp",
@@ -11,79 +42,773 @@ fn main() -> eframe::Result<()> {
     )
 }
 struct MathApp {
-    current_function: CurrentFunction,
+    current_function: usize,
     functions: Vec<Function>,
     theme: Theme,
+    language: Language,
     help_opened: bool,
+    explain_opened: bool,
+    all_roots_opened: bool,
+    history_opened: bool,
+    export_opened: bool,
+    export_options: ExportOptions,
+    export_path: String,
+    export_status: String,
+    playing: bool,
+    play_delay: f64,
+    last_step_time: f64,
+    plot_bounds: ([f64; 2], [f64; 2]),
+    plot_export_opened: bool,
+    plot_export_print: bool,
+    plot_export_size: [u32; 2],
+    plot_export_scale: f32,
+    plot_export_path: String,
+    plot_export_status: String,
+    compare_opened: bool,
+    /// The methods ticked for the next comparison, two to four of them.
+    compare_methods: Vec<Method>,
+    comparison: Option<Comparison>,
+    session_opened: bool,
+    session_path: String,
+    session_status: String,
+    /// The title of the loaded problem set.
+    problem_set: String,
+    problem_path: String,
+    new_equation: String,
+    new_equation_error: Option<ParseError>,
+    /// While the quiz is open the student has to guess each next iterate before it is shown.
+    quiz_opened: bool,
+    quiz: Quiz,
+    quiz_path: String,
+    quiz_status: String,
 }
 struct Function {
     title: String,
+    expr: Expr,
+    builtin: bool,
+    /// Where f is defined; a and b are kept inside it.
+    domain: Domain,
+    /// The methods offered for this equation.
+    methods: Vec<Method>,
+    /// Known roots to check the answer against.
+    roots: Vec<f64>,
     temp_a: f64,
     temp_b: f64,
     temp_eps: f64,
     a: f64,
     b: f64,
     eps: f64,
-    current_function: CurrentFunction,
-    fixed: f64,
-    x2: f64,
     current_iteration: i64,
     reached_eps: bool,
-    is_error: bool,
+    error: Option<SolverError>,
     current_plot_vec: HashMap<String, Vec<[f64; 2]>>,
-    lines: Vec<Vec<[f64; 2]>>,
     plot_max_y: f64,
+    /// The graph of f as unbroken segments, sampled for `curve_view`.
+    curve: Vec<Vec<[f64; 2]>>,
+    /// The visible x and y ranges the curve was sampled for.
+    curve_view: Option<[f64; 4]>,
+    derivative_mismatch: Option<DerivativeMismatch>,
+    method: Method,
+    stop: StopCriteria,
+    max_iterations: usize,
+    /// Wall-clock limit of "Solve" in seconds.
+    time_budget: f64,
+    solver: Box<dyn RootSolver>,
+    /// The background "Solve" run, if one is in progress.
+    worker: Option<Worker>,
+    history: Vec<Iteration>,
+    /// The solver state after each entry of `history`.
+    states: Vec<Box<dyn RootSolver>>,
+    /// How many entries of `history` are shown; less than its length after stepping back.
+    position: usize,
+    selected_iteration: Option<usize>,
+    analysis: Analysis,
+    scan_from: f64,
+    scan_to: f64,
+    brackets: Vec<Bracket>,
+    all_roots: Vec<Run>,
+    /// The "Find all roots" run in progress, if any.
+    scan: Option<Scan>,
+    /// Solved and total brackets of a scan that was cancelled, so `all_roots` is incomplete.
+    scan_cancelled: Option<(usize, usize)>,
 }
 impl Function {
-    fn new(name: &str, left: f64, right: f64, precision: f64, place: CurrentFunction) -> Function {
+    fn new(
+        name: &str,
+        expr: Expr,
+        domain: Domain,
+        left: f64,
+        right: f64,
+        precision: f64,
+        method: Method,
+    ) -> Function {
         Function {
             title: name.to_string(),
+            builtin: false,
+            domain,
+            methods: Method::ALL.to_vec(),
+            roots: vec![],
             temp_a: left,
             temp_b: right,
             temp_eps: precision,
             a: left,
             b: right,
             eps: precision,
-            current_function: place,
-            fixed: right,
-            x2: right,
             current_iteration: -1,
             reached_eps: false,
-            is_error: false,
+            error: None,
             current_plot_vec: HashMap::<String, Vec<[f64; 2]>>::new(),
-            lines: vec![vec![]],
             plot_max_y: 1.0,
+            curve: vec![],
+            curve_view: None,
+            derivative_mismatch: expr.check_derivatives(left, right, 100),
+            analysis: analysis::analyze(&expr, left, right, 1000),
+            scan_from: -10.0,
+            scan_to: 10.0,
+            brackets: vec![],
+            all_roots: vec![],
+            scan: None,
+            scan_cancelled: None,
+            expr,
+            method,
+            stop: StopCriteria::default(),
+            max_iterations: solver::MAX_ITERATIONS,
+            time_budget: 5.0,
+            solver: method.solver(left, right, precision),
+            worker: None,
+            history: vec![],
+            states: vec![],
+            position: 0,
+            selected_iteration: None,
+        }
+    }
+    /// An equation from a problem set, started on its suggested bracket with the first allowed method.
+    fn problem(problem: &Problem) -> Function {
+        Function {
+            builtin: true,
+            methods: problem.methods.clone(),
+            roots: problem.roots.clone(),
+            ..Function::new(
+                problem.title.as_str(),
+                problem.expr.clone(),
+                problem.domain.clone(),
+                problem.bracket.0,
+                problem.bracket.1,
+                problem.eps,
+                problem.methods[0],
+            )
         }
     }
+    /// A typed-in equation: its domain is inferred and [-1, 1] is moved into it.
+    fn user(expression: &str, expr: Expr) -> Function {
+        let domain: Domain = domain::infer(&expr);
+        let (left, right) = domain.bracket(-1.0, 1.0);
+        Function::new(expression, expr, domain, left, right, 0.001, Method::Chord)
+    }
+    fn saved(&self) -> SavedFunction {
+        SavedFunction {
+            title: self.title.clone(),
+            expression: self.expr.to_string(),
+            builtin: self.builtin,
+            domain: self.domain.clone(),
+            methods: self.methods.clone(),
+            roots: self.roots.clone(),
+            method: self.method,
+            a: self.a,
+            b: self.b,
+            eps: self.eps,
+            temp_a: self.temp_a,
+            temp_b: self.temp_b,
+            temp_eps: self.temp_eps,
+            stop: self.stop.clone(),
+            max_iterations: self.max_iterations,
+            time_budget: self.time_budget,
+            scan_from: self.scan_from,
+            scan_to: self.scan_to,
+            history: self.history.clone(),
+            position: self.position,
+        }
+    }
+    /// Rebuilds a saved equation with its iterations as they were saved. The solver state
+    /// for further steps is brought back by replaying the run on a worker thread.
+    fn restore(saved: &SavedFunction, expr: Expr) -> Function {
+        let mut function: Function = Function {
+            builtin: saved.builtin,
+            methods: saved.methods.clone(),
+            roots: saved.roots.clone(),
+            temp_a: saved.temp_a,
+            temp_b: saved.temp_b,
+            temp_eps: saved.temp_eps,
+            scan_from: saved.scan_from,
+            scan_to: saved.scan_to,
+            stop: saved.stop.clone(),
+            time_budget: saved.time_budget,
+            ..Function::new(
+                saved.title.as_str(),
+                expr,
+                saved.domain.clone(),
+                saved.a,
+                saved.b,
+                saved.eps,
+                saved.method,
+            )
+        };
+        function.set_max_iterations(saved.max_iterations);
+        function.prepare_plot();
+        function.history = saved.history.clone();
+        function.reached_eps = function
+            .history
+            .last()
+            .is_some_and(|last| function.stops(last));
+        function.go_to(saved.position);
+        function.replay(saved.history.len());
+        function
+    }
+    /// Keeps the typed-in a and b inside the domain.
+    fn clamp_bracket(&mut self) {
+        self.temp_a = self.domain.clamp(self.temp_a);
+        self.temp_b = self.domain.clamp(self.temp_b);
+    }
+    /// A fresh run on new data that keeps what the user typed in the UI.
+    fn rebuild(&self, left: f64, right: f64, precision: f64, method: Method) -> Function {
+        let mut function: Function = Function {
+            builtin: self.builtin,
+            methods: self.methods.clone(),
+            roots: self.roots.clone(),
+            temp_a: self.temp_a,
+            temp_b: self.temp_b,
+            temp_eps: self.temp_eps,
+            scan_from: self.scan_from,
+            scan_to: self.scan_to,
+            brackets: self.brackets.clone(),
+            stop: self.stop.clone(),
+            time_budget: self.time_budget,
+            ..Function::new(
+                self.title.as_str(),
+                self.expr.clone(),
+                self.domain.clone(),
+                left,
+                right,
+                precision,
+                method,
+            )
+        };
+        function.set_max_iterations(self.max_iterations);
+        function
+    }
     fn f(&self, x: f64) -> f64 {
-        match self.current_function {
-            CurrentFunction::First => f64::exp(-x) * f64::cos(x * std::f64::consts::PI),
-            CurrentFunction::Second => {
-                3.0 * f64::powi(x, 4) - 4.0 * f64::powi(x, 3) - 12.0 * x * x + 2.0
+        self.expr.eval(x)
+    }
+    fn report(&self) -> Report<'_> {
+        Report {
+            title: self.title.as_str(),
+            method: self.method.name(),
+            a: self.a,
+            b: self.b,
+            eps: self.eps,
+            history: self.shown(),
+            estimates: self
+                .shown()
+                .iter()
+                .map(|iteration| self.analysis.error_estimate(self.method, iteration))
+                .collect(),
+            stops: self
+                .shown()
+                .iter()
+                .map(|iteration| self.stops(iteration))
+                .collect(),
+            error: self.shown_error(),
+        }
+    }
+    /// The iterations up to the current position on the timeline.
+    fn shown(&self) -> &[Iteration] {
+        &self.history[..self.position]
+    }
+    /// The error is only shown at the end of the timeline, where it happened.
+    fn shown_error(&self) -> Option<&SolverError> {
+        self.error
+            .as_ref()
+            .filter(|_| self.position == self.history.len())
+    }
+    /// Whether the shown iteration satisfies the stop rules.
+    fn shown_reached_eps(&self) -> bool {
+        if self.position == self.history.len() {
+            self.reached_eps
+        } else {
+            self.stops(&self.history[self.position - 1])
+        }
+    }
+    fn stops(&self, iteration: &Iteration) -> bool {
+        self.stop
+            .stops(self.method, iteration, &self.analysis, self.eps)
+    }
+    /// Applies changed stop rules to the latest iteration.
+    fn set_stop(&mut self, stop: StopCriteria) {
+        self.stop = stop;
+        if self.error.is_none() {
+            self.reached_eps = self.history.last().is_some_and(|last| self.stops(last));
+        }
+    }
+    fn can_step(&self) -> bool {
+        self.worker.is_none()
+            && (self.position < self.history.len()
+                || (!self.reached_eps && self.error.is_none() && self.replayed()))
+    }
+    /// Whether the solver state is known after every iteration; a restored run that has
+    /// not been replayed yet only has the saved iterations.
+    fn replayed(&self) -> bool {
+        self.states.len() == self.history.len()
+    }
+    /// Shows the next iteration: one already on the timeline, or a new one from the solver.
+    fn step(&mut self) {
+        if self.position < self.history.len() {
+            self.go_to(self.position + 1);
+            return;
+        }
+        match self.solver.step(&self.expr) {
+            Ok(iteration) => {
+                self.reached_eps = self.stops(&iteration);
+                self.history.push(iteration);
+                self.states.push(self.solver.snapshot());
+                self.go_to(self.history.len());
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+    fn go_to(&mut self, position: usize) {
+        self.position = position.min(self.history.len());
+        self.current_iteration = self.position as i64;
+    }
+    /// Drops the iterations after the current position and continues from there with `eps`.
+    fn branch(&mut self, eps: f64) {
+        self.history.truncate(self.position);
+        self.states.truncate(self.position);
+        self.solver = match self.states.last() {
+            Some(state) => state.snapshot(),
+            None => self.method.solver(self.a, self.b, eps),
+        };
+        self.solver.set_limits(eps, self.max_iterations);
+        self.eps = eps;
+        self.error = None;
+        // The tolerance is checked again by the next step.
+        self.reached_eps = false;
+    }
+    fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+        self.solver.set_limits(self.eps, max_iterations);
+        match self.error {
+            // A higher cap lets the run continue from where it stopped.
+            Some(SolverError::IterationLimit { iterations, .. }) if iterations < max_iterations => {
+                self.error = None
             }
-            CurrentFunction::Third => x * x - 5.0 * f64::sin(x),
-            CurrentFunction::Fourth => 0.1 * x * x - x * f64::ln(x),
+            Some(SolverError::TimeLimit { .. }) => self.error = None,
+            _ => {}
+        }
+    }
+    /// Every "Solve" gets the whole budget, so a run that ran out of time can go on.
+    fn set_time_budget(&mut self, seconds: f64) {
+        self.time_budget = seconds;
+        if let Some(SolverError::TimeLimit { .. }) = self.error {
+            self.error = None;
+        }
+    }
+    /// Whether a worker thread is busy with this equation; a rebuild would drop its results.
+    fn busy(&self) -> bool {
+        self.worker.is_some() || self.scan.is_some()
+    }
+    /// Runs a fresh solver on a worker thread for `until` iterations to get back the states
+    /// of a restored run.
+    fn replay(&mut self, until: usize) {
+        if until == 0 {
+            return;
         }
+        self.worker = Some(Worker::spawn(Job {
+            solver: self.solver.snapshot(),
+            expr: self.expr.clone(),
+            method: self.method,
+            analysis: self.analysis.clone(),
+            stop: self.stop.clone(),
+            eps: self.eps,
+            max_iterations: until,
+            done: 0,
+            x: f64::NAN,
+            budget: std::time::Duration::MAX,
+            until,
+        }));
     }
-    fn f_der2(&self, x: f64) -> f64 {
-        match self.current_function {
-            CurrentFunction::First => {
-                -(f64::cos(std::f64::consts::PI * x)
-                    + std::f64::consts::PI * f64::sin(std::f64::consts::PI))
-                    * f64::exp(-x)
+    /// Runs the solver on a worker thread until a stop rule, an error or a limit.
+    fn solve_in_background(&mut self) {
+        self.go_to(self.history.len());
+        if !self.can_step() {
+            return;
+        }
+        self.worker = Some(Worker::spawn(Job {
+            solver: self.solver.snapshot(),
+            expr: self.expr.clone(),
+            method: self.method,
+            analysis: self.analysis.clone(),
+            stop: self.stop.clone(),
+            eps: self.eps,
+            max_iterations: self.max_iterations,
+            done: self.history.len(),
+            x: self.history.last().map_or(f64::NAN, |last| last.x),
+            budget: std::time::Duration::from_secs_f64(self.time_budget),
+            until: usize::MAX,
+        }));
+    }
+    /// Takes the iterations the worker has streamed so far.
+    fn poll_worker(&mut self) {
+        let Some(worker) = &mut self.worker else {
+            return;
+        };
+        let mut finished: bool = false;
+        // The timeline follows a run that grows, not a replay of saved iterations.
+        let following: bool = self.position == self.history.len();
+        for message in worker.poll() {
+            match message {
+                Message::Iteration(iteration, state) => {
+                    match self.history.get(self.states.len()) {
+                        None => self.history.push(iteration),
+                        Some(saved) if saved.x == iteration.x => {}
+                        // A run branched with another eps is not reproduced by one solver;
+                        // the saved iterations stay and the states end where they differ.
+                        Some(_) => {
+                            worker.cancel();
+                            finished = true;
+                            break;
+                        }
+                    }
+                    self.states.push(state);
+                }
+                Message::Reached => {
+                    self.reached_eps = true;
+                    finished = true;
+                }
+                Message::Failed(error) => {
+                    self.error = Some(error);
+                    finished = true;
+                }
+                Message::Cancelled | Message::Replayed => finished = true,
+            }
+        }
+        if finished {
+            self.worker = None;
+            // Partial results stay: stepping continues from the last streamed iteration.
+            if let Some(state) = self.states.last() {
+                self.solver = state.snapshot();
             }
-            CurrentFunction::Second => 12.0 * f64::powi(x, 3) - 12.0 * f64::powi(x, 2) - 24.0 * x,
-            CurrentFunction::Third => 2.0 * x - 5.0 * f64::cos(x),
-            CurrentFunction::Fourth => 0.2 * x - f64::ln(x) - 1.0,
         }
+        if following || self.position > self.history.len() {
+            self.go_to(self.history.len());
+        }
+    }
+    /// Solves every bracket between "from" and "to" on a worker thread.
+    fn find_all_roots(&mut self) {
+        self.brackets = analysis::find_brackets(
+            &self.expr,
+            f64::min(self.scan_from, self.scan_to),
+            f64::max(self.scan_from, self.scan_to),
+            200,
+        );
+        self.all_roots.clear();
+        self.scan_cancelled = None;
+        self.scan = Some(Scan::spawn(ScanJob {
+            expr: self.expr.clone(),
+            method: self.method,
+            brackets: self.brackets.clone(),
+            stop: self.stop.clone(),
+            eps: self.eps,
+            max_iterations: self.max_iterations,
+            budget: std::time::Duration::from_secs_f64(self.time_budget),
+        }));
+    }
+    /// Takes the roots the scan has found so far.
+    fn poll_scan(&mut self) {
+        let Some(scan) = &mut self.scan else {
+            return;
+        };
+        self.all_roots.extend(scan.poll());
+        if scan.cancelled() {
+            self.scan_cancelled = Some((scan.done, scan.total));
+            self.scan = None;
+        } else if scan.finished() {
+            self.scan = None;
+        }
+    }
+    /// Sets up the borders and the height of the plot after a rebuild.
+    fn prepare_plot(&mut self) {
+        if self.current_iteration != -1 {
+            return;
+        }
+        // The largest |f| on [a, b] sets the initial view and the height of the borders.
+        let samples: usize = 200;
+        let plot_max_y: f64 = (0..=samples)
+            .map(|i| self.f(self.a + (self.b - self.a) * i as f64 / samples as f64))
+            .filter(|y| y.is_finite())
+            .fold(0.0, |max: f64, y| max.max(y.abs()));
+        let plot_max_y: f64 = if plot_max_y > 0.0 { plot_max_y } else { 1.0 };
+        let border_left: Vec<[f64; 2]> = vec![[self.a, -plot_max_y], [self.a, plot_max_y]];
+        self.current_plot_vec
+            .insert(String::from("Left border"), border_left);
+        let border_right: Vec<[f64; 2]> = vec![[self.b, -plot_max_y], [self.b, plot_max_y]];
+        self.current_plot_vec
+            .insert(String::from("Right border"), border_right);
+        self.plot_max_y = plot_max_y;
+        self.curve_view = None;
+        self.current_iteration += 1;
     }
+    /// Samples the curve again when the visible range of the plot changed.
+    fn follow_view(&mut self, bounds: &egui_plot::PlotBounds) -> bool {
+        let view: [f64; 4] = [
+            bounds.min()[0],
+            bounds.max()[0],
+            bounds.min()[1],
+            bounds.max()[1],
+        ];
+        if self.curve_view == Some(view) {
+            return false;
+        }
+        self.curve = scene::sample_curve(&self.expr, view[0], view[1], view[3] - view[2]);
+        self.curve_view = Some(view);
+        true
+    }
+    /// What the plot shows, shared by the window and the image export.
+    /// `progress` in [0, 1] animates the construction of the last iteration.
+    fn scene(&self, theme: &Theme, language: Language, progress: f64) -> Scene {
+        let mut scene: Scene = Scene::default();
+        let height: f64 = self.plot_max_y.abs();
+        if !self.domain.is_real_line() {
+            // Infinite ends are drawn to the edge of the visible range.
+            let (left, right) = match self.curve_view {
+                Some(view) => (view[0], view[1]),
+                None => (self.a - height, self.b + height),
+            };
+            for interval in self.domain.intervals.iter() {
+                let lo: f64 = interval.lo.value.max(left);
+                let hi: f64 = interval.hi.value.min(right);
+                if lo < hi {
+                    scene.polygon(
+                        language.tr("Domain"),
+                        theme.green.gamma_multiply(0.08),
+                        None,
+                        vec![[lo, -height], [hi, -height], [hi, height], [lo, height]],
+                    );
+                }
+            }
+        }
+        if let Some((lo, hi)) = self.shown().last().and_then(|iteration| iteration.bracket) {
+            scene.polygon(
+                language.tr("Bracket"),
+                theme.yellow.gamma_multiply(0.25),
+                Some(theme.yellow),
+                vec![[lo, -height], [hi, -height], [hi, height], [lo, height]],
+            );
+        }
+        for failure in self.analysis.failures.iter() {
+            let (color, name) = match failure.condition {
+                Condition::Defined => (theme.red, "f is undefined"),
+                Condition::Monotonic => (theme.peach, "f' changes sign"),
+                Condition::Convexity => (theme.mauve, "f'' changes sign"),
+            };
+            scene.polygon(
+                language.tr(name),
+                color.gamma_multiply(0.2),
+                None,
+                vec![
+                    [failure.from, -height],
+                    [failure.to, -height],
+                    [failure.to, height],
+                    [failure.from, height],
+                ],
+            );
+        }
+        let palette = theme.palette();
+        for (i, run) in self.all_roots.iter().enumerate() {
+            let color = palette[i % palette.len()];
+            let name: String = language.fill("Root #{}", &[(i + 1).to_string()]);
+            for (_, line) in run.iterations.iter().flat_map(|it| it.lines.iter()) {
+                scene.line(&name, color, 1.0, line.clone());
+            }
+            if let Some(root) = run.root() {
+                scene.points(&name, color, 4.0, vec![[root.x, 0.0]]);
+            }
+        }
+        for (i, iteration) in self.shown().iter().enumerate() {
+            let animated: bool = progress < 1.0 && i + 1 == self.position;
+            for (j, (kind, line)) in iteration.lines.iter().enumerate() {
+                let (color, name) = match kind {
+                    LineKind::Chord => (theme.teal, language.tr("Chord")),
+                    LineKind::Tangent => (theme.blue, language.tr("Tangent")),
+                    LineKind::Vertical => (theme.green, language.tr("Showcase")),
+                };
+                if animated {
+                    if *kind != LineKind::Vertical {
+                        let previous: Option<&Vec<[f64; 2]>> = i
+                            .checked_sub(1)
+                            .and_then(|k| self.history[k].lines.get(j))
+                            .filter(|(previous_kind, _)| previous_kind == kind)
+                            .map(|(_, points)| points);
+                        scene.line(name, color, 1.0, sliding_line(previous, line, progress));
+                    }
+                    continue;
+                }
+                if self.selected_iteration == Some(i) {
+                    scene.line(
+                        language.tr("Selected iteration"),
+                        theme.red,
+                        3.0,
+                        line.clone(),
+                    );
+                } else {
+                    scene.line(name, color, 1.0, line.clone());
+                }
+            }
+            if animated && iteration.x_prev.is_finite() {
+                let x: f64 = iteration.x_prev + (iteration.x - iteration.x_prev) * progress;
+                scene.points(language.tr("Next x"), theme.green, 4.0, vec![[x, 0.0]]);
+            }
+        }
+        for segment in self.curve.iter() {
+            scene.line(self.title.as_str(), theme.red, 1.0, segment.clone());
+        }
+        if !self.roots.is_empty() {
+            let roots: Vec<[f64; 2]> = self.roots.iter().map(|root| [*root, 0.0]).collect();
+            scene.points(language.tr("Expected root"), theme.sky, 3.0, roots);
+        }
+        for (key, name, color) in [
+            ("Left border", "Left and Right borders", theme.pink),
+            ("Right border", "Left and Right borders", theme.pink),
+        ] {
+            if let Some(points) = self.current_plot_vec.get(key) {
+                scene.line(language.tr(name), color, 1.0, points.clone());
+            }
+        }
+        scene
+    }
+}
+/// Several methods on the same equation, interval and eps, stepped together.
+struct Comparison {
+    title: String,
+    lanes: Vec<Lane>,
+    /// The root the final errors are measured against, found by Brent's method.
+    root: Option<f64>,
 }
-#[derive(PartialEq, Eq, Clone, Copy)]
-enum CurrentFunction {
-    First,
-    Second,
-    Third,
-    Fourth,
+struct Lane {
+    function: Function,
+    /// Time spent inside the solver, or on the worker thread for "Solve".
+    time: std::time::Duration,
+}
+impl Comparison {
+    fn new(function: &Function, methods: &[Method]) -> Comparison {
+        let reference: Run =
+            solver::solve(Method::Brent, &function.expr, function.a, function.b, 1e-15);
+        Comparison {
+            title: function.title.clone(),
+            lanes: methods
+                .iter()
+                .map(|method| Lane {
+                    function: function.rebuild(function.a, function.b, function.eps, *method),
+                    time: std::time::Duration::ZERO,
+                })
+                .collect(),
+            root: reference.root().map(|root| root.x),
+        }
+    }
+    fn can_step(&self) -> bool {
+        self.lanes.iter().any(|lane| lane.function.can_step())
+    }
+    /// One more iteration of every method that has not stopped yet.
+    fn step(&mut self) {
+        for lane in self.lanes.iter_mut() {
+            if lane.function.can_step() {
+                let started: std::time::Instant = std::time::Instant::now();
+                lane.function.step();
+                lane.time += started.elapsed();
+            }
+        }
+    }
+    /// Runs every method that has not stopped yet on its own worker thread.
+    fn solve_in_background(&mut self) {
+        for lane in self.lanes.iter_mut() {
+            lane.function.solve_in_background();
+        }
+    }
+    fn running(&self) -> bool {
+        self.lanes.iter().any(|lane| lane.function.worker.is_some())
+    }
+    fn cancel(&self) {
+        for worker in self
+            .lanes
+            .iter()
+            .filter_map(|lane| lane.function.worker.as_ref())
+        {
+            worker.cancel();
+        }
+    }
+    /// Takes what the workers have streamed and adds their time once they finish.
+    fn poll_workers(&mut self) {
+        for lane in self.lanes.iter_mut() {
+            let Some(worker) = &lane.function.worker else {
+                continue;
+            };
+            let elapsed: std::time::Duration = worker.elapsed();
+            lane.function.poll_worker();
+            if lane.function.worker.is_none() {
+                lane.time += elapsed;
+            }
+        }
+    }
+    /// The slowest lane's progress, the iterations of all lanes and the longest run time.
+    fn progress(&self) -> (f32, usize, std::time::Duration) {
+        let workers = self
+            .lanes
+            .iter()
+            .filter_map(|lane| lane.function.worker.as_ref());
+        (
+            workers.clone().map(Worker::fraction).fold(1.0, f32::min),
+            self.lanes
+                .iter()
+                .map(|lane| lane.function.history.len())
+                .sum(),
+            workers.map(Worker::elapsed).max().unwrap_or_default(),
+        )
+    }
+}
+/// A tick label the way egui_plot writes it, so that the decimal separator can be swapped.
+fn axis_tick(value: f64, digits: usize) -> String {
+    let scale: f64 = 10f64.powi(digits as i32);
+    if value.abs() > scale {
+        format!("{:+e}", value.round())
+    } else if value != 0.0 && value.abs() < 1.0 / scale {
+        format!("{:+e}", value)
+    } else {
+        ((value * scale).round() / scale).to_string()
+    }
+}
+/// A line moving from where the previous iteration drew it towards `line`,
+/// or growing from its first point when there is nothing to move from.
+fn sliding_line(
+    previous: Option<&Vec<[f64; 2]>>,
+    line: &[[f64; 2]],
+    progress: f64,
+) -> Vec<[f64; 2]> {
+    let lerp = |from: [f64; 2], to: [f64; 2]| -> [f64; 2] {
+        [
+            from[0] + (to[0] - from[0]) * progress,
+            from[1] + (to[1] - from[1]) * progress,
+        ]
+    };
+    match previous {
+        Some(previous) if previous.len() == line.len() => previous
+            .iter()
+            .zip(line)
+            .map(|(from, to)| lerp(*from, *to))
+            .collect(),
+        _ => match (line.first(), line.last()) {
+            (Some(first), Some(last)) => vec![*first, lerp(*first, *last)],
+            _ => vec![],
+        },
+    }
 }
 impl MathApp {
     fn new(cc: &eframe::CreationContext<'_>) -> MathApp {
@@ -110,69 +835,206 @@ impl MathApp {
         ]
         .into();
         cc.egui_ctx.set_style(style);
+        cc.egui_ctx.set_fonts(locale::fonts());
 
-        MathApp {
-            current_function: CurrentFunction::First,
-            functions: vec![
-                Function::new(
-                    "exp(-x) * cos(πx)",
-                    -1.0,
-                    1.0,
-                    0.001,
-                    CurrentFunction::First,
-                ),
-                Function::new(
-                    "3x⁴ - 4x³ - 12x² + 2",
-                    -1.0,
-                    1.0,
-                    0.001,
-                    CurrentFunction::Second,
-                ),
-                Function::new("x² - 5sin(x)", -1.0, 1.0, 0.001, CurrentFunction::Third),
-                Function::new("0.1x - xln(x)", -1.0, 1.0, 0.001, CurrentFunction::Fourth),
-            ],
+        let mut app: MathApp = MathApp {
+            current_function: 0,
+            functions: vec![],
             theme: LATTE,
+            language: Language::from_environment(),
             help_opened: false,
+            explain_opened: false,
+            all_roots_opened: false,
+            history_opened: false,
+            export_opened: false,
+            export_options: ExportOptions::default(),
+            export_path: String::from("iterations.csv"),
+            export_status: String::new(),
+            playing: false,
+            play_delay: 1.0,
+            last_step_time: f64::NEG_INFINITY,
+            plot_bounds: ([-1.0, -1.0], [1.0, 1.0]),
+            plot_export_opened: false,
+            plot_export_print: false,
+            plot_export_size: [800, 500],
+            plot_export_scale: 2.0,
+            plot_export_path: String::from("plot"),
+            plot_export_status: String::new(),
+            compare_opened: false,
+            compare_methods: vec![Method::Chord, Method::Newton],
+            comparison: None,
+            session_opened: false,
+            session_path: String::from("session.json"),
+            session_status: String::new(),
+            problem_set: String::new(),
+            problem_path: String::from("problems.json"),
+            new_equation: String::new(),
+            new_equation_error: None,
+            quiz_opened: false,
+            quiz: Quiz::default(),
+            quiz_path: String::from("quiz.csv"),
+            quiz_status: String::new(),
+        };
+        let variant: ProblemSet =
+            problems::from_json(problems::VARIANT_8).expect("the built-in problem set must load");
+        app.load_problem_set(variant);
+        if let Some(text) = cc
+            .storage
+            .and_then(|storage| storage.get_string(SESSION_KEY))
+        {
+            // A session that no longer loads is dropped in favour of the defaults.
+            if let Err(error) = session::from_json(&text).and_then(|session| app.restore(session)) {
+                app.session_status = app
+                    .language
+                    .fill("The last session could not be restored: {}", &[error]);
+            }
+        }
+        app
+    }
+    /// Replaces the equations of the previous problem set; typed-in ones stay after them.
+    fn load_problem_set(&mut self, set: ProblemSet) {
+        self.functions.retain(|function| !function.builtin);
+        let loaded: Vec<Function> = set.problems.iter().map(Function::problem).collect();
+        self.functions.splice(0..0, loaded);
+        self.problem_set = set.title;
+        self.current_function = 0;
+        self.playing = false;
+        self.comparison = None;
+    }
+    fn session(&self) -> Session {
+        Session {
+            problem_set: self.problem_set.clone(),
+            theme: THEMES
+                .iter()
+                .find(|(_, theme)| *theme == self.theme)
+                .map_or("LATTE", |(name, _)| name)
+                .to_string(),
+            language: self.language.key().to_string(),
+            current_function: self.current_function,
+            functions: self.functions.iter().map(Function::saved).collect(),
+        }
+    }
+    /// Replaces the equations, runs, theme and language with the ones from `session`.
+    fn restore(&mut self, session: Session) -> Result<(), String> {
+        let mut functions: Vec<Function> = vec![];
+        for saved in session.functions.iter() {
+            let expr: Expr = expr::parse(saved.expression.as_str())
+                .map_err(|error| format!("\"{}\": {}", saved.expression, error))?;
+            functions.push(Function::restore(saved, expr));
+        }
+        if let Some((_, theme)) = THEMES.iter().find(|(name, _)| *name == session.theme) {
+            self.theme = *theme;
+        }
+        if let Some(language) = Language::from_key(&session.language) {
+            self.language = language;
         }
+        self.functions = functions;
+        self.problem_set = session.problem_set;
+        self.current_function = session.current_function;
+        self.playing = false;
+        self.comparison = None;
+        Ok(())
     }
 }
+/// The key of the last session in eframe's storage.
+const SESSION_KEY: &str = "session";
 impl eframe::App for MathApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(SESSION_KEY, session::to_json(&self.session()));
+    }
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         set_theme(ctx, self.theme);
-        let current: usize = match self.current_function {
-            CurrentFunction::First => 0,
-            CurrentFunction::Second => 1,
-            CurrentFunction::Third => 2,
-            CurrentFunction::Fourth => 3,
-        };
+        let language: Language = self.language;
+        let current: usize = self.current_function;
+        let now: f64 = ctx.input(|input| input.time);
+        for function in self.functions.iter_mut() {
+            function.poll_worker();
+            function.poll_scan();
+        }
+        if let Some(comparison) = &mut self.comparison {
+            comparison.poll_workers();
+        }
+        if self
+            .functions
+            .iter()
+            .any(|function| function.worker.is_some() || function.scan.is_some())
+            || self.comparison.as_ref().is_some_and(Comparison::running)
+        {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        }
+        if self.playing {
+            if !self.functions[current].can_step() {
+                self.playing = false;
+            } else if now - self.last_step_time >= self.play_delay {
+                self.functions[current].step();
+                self.last_step_time = now;
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+                    self.play_delay - (now - self.last_step_time),
+                ));
+            }
+        }
+        // The construction of a new iteration takes part of the delay to slide into place.
+        let animation: f64 =
+            ((now - self.last_step_time) / (0.6 * self.play_delay)).clamp(0.0, 1.0);
+        if animation < 1.0 {
+            ctx.request_repaint();
+        }
+        let mut removed_function: Option<usize> = None;
+        let mut opened_session: Option<Session> = None;
+        let mut opened_problems: Option<ProblemSet> = None;
         egui::TopBottomPanel::top("Title").show(ctx, |ui| {
             ui.horizontal_centered(|ui| {
                 ui.horizontal(|ui| {
-                    ui.heading("Chord method Showcase.");
-                    ui.label("Source code:");
+                    ui.heading(language.tr("Chord method Showcase."));
+                    ui.label(language.tr("Source code:"));
                     ui.hyperlink_to("🔌GitHub", "https://github.com/bmg-c/opr");
                 });
                 ui.with_layout(
                     egui::Layout::right_to_left(eframe::emath::Align::Center),
                     |ui| {
-                        if ui.add(egui::Button::new("HELP")).clicked() {
-                            self.help_opened = if self.help_opened == false {
-                                true
-                            } else {
-                                false
-                            };
+                        if ui.add(egui::Button::new(language.tr("HELP"))).clicked() {
+                            self.help_opened = !self.help_opened;
+                        }
+                        if ui.add(egui::Button::new(language.tr("Explain"))).clicked() {
+                            self.explain_opened = !self.explain_opened;
+                        }
+                        if ui.add(egui::Button::new(language.tr("Quiz"))).clicked() {
+                            self.quiz_opened = !self.quiz_opened;
+                            self.playing = false;
+                        }
+                        if ui.add(egui::Button::new(language.tr("Session"))).clicked() {
+                            self.session_opened = !self.session_opened;
+                        }
+                        if ui.add(egui::Button::new(language.tr("Compare"))).clicked() {
+                            self.compare_opened = !self.compare_opened;
                         }
                         if ui
-                            .add(egui::Button::new(format!(
-                                "Theme: {}",
-                                match self.theme {
-                                    MOCHA => "MOCHA",
-                                    MACCHIATO => "MACCHIATO",
-                                    FRAPPE => "FRAPPE",
-                                    LATTE => "LATTE",
-                                    _ => "LATTE",
-                                }
-                            )))
+                            .add(egui::Button::new(language.tr("Export plot")))
+                            .clicked()
+                        {
+                            self.plot_export_opened = !self.plot_export_opened;
+                        }
+                        if ui.add(egui::Button::new(language.tr("Export"))).clicked() {
+                            self.export_opened = !self.export_opened;
+                        }
+                        if ui.add(egui::Button::new(language.tr("History"))).clicked() {
+                            self.history_opened = !self.history_opened;
+                        }
+                        if ui
+                            .add(egui::Button::new(
+                                language.fill(
+                                    "Theme: {}",
+                                    &[match self.theme {
+                                        MOCHA => "MOCHA",
+                                        MACCHIATO => "MACCHIATO",
+                                        FRAPPE => "FRAPPE",
+                                        LATTE => "LATTE",
+                                        _ => "LATTE",
+                                    }
+                                    .to_string()],
+                                ),
+                            ))
                             .clicked()
                         {
                             self.theme = match self.theme {
@@ -183,8 +1045,19 @@ impl eframe::App for MathApp {
                                 _ => LATTE,
                             }
                         }
+                        if ui
+                            .add(egui::Button::new(
+                                language.fill("Language: {}", &[language.name().to_string()]),
+                            ))
+                            .clicked()
+                        {
+                            self.language = match language {
+                                Language::English => Language::Russian,
+                                Language::Russian => Language::English,
+                            }
+                        }
                         ui.hyperlink_to("Ivan ", "https://github.com/bmg-c");
-                        ui.label("Author:");
+                        ui.label(language.tr("Author:"));
                     },
                 );
             });
@@ -195,12 +1068,7 @@ impl eframe::App for MathApp {
             let x_decimals: usize = {
                 let mut i: usize = 0;
                 let mut dec: usize = 0;
-                for part in self.functions[current]
-                    .eps
-                    .clone()
-                    .to_string()
-                    .split(".")
-                {
+                for part in self.functions[current].eps.clone().to_string().split(".") {
                     i += 1;
                     dec = part.len()
                 }
@@ -210,71 +1078,318 @@ impl eframe::App for MathApp {
                 }
             } + 1;
             ui.horizontal(|ui| {
-                ui.heading(if self.functions[current].current_iteration != 0 {
-                    format!(
-                        "x = {}, f(x) = {}",
-                        format!("{:.1$}", self.functions[current].x2, x_decimals),
-                        format!(
-                            "{:.1$}",
-                            self.functions[current].f(self.functions[current].x2),
-                            x_decimals
-                        )
-                    )
-                } else {
-                    "".to_string()
+                ui.heading(match self.functions[current].shown().last() {
+                    Some(iteration) => match iteration.bracket {
+                        Some((lo, hi)) => language.fill(
+                            "x = {}, f(x) = {}, root in [{}; {}]",
+                            &[
+                                language.fixed(iteration.x, x_decimals),
+                                language.fixed(iteration.fx, x_decimals),
+                                language.fixed(lo, x_decimals),
+                                language.fixed(hi, x_decimals),
+                            ],
+                        ),
+                        None => language.fill(
+                            "x = {}, f(x) = {}",
+                            &[
+                                language.fixed(iteration.x, x_decimals),
+                                language.fixed(iteration.fx, x_decimals),
+                            ],
+                        ),
+                    },
+                    None => "".to_string(),
                 });
                 ui.with_layout(
                     egui::Layout::right_to_left(eframe::emath::Align::RIGHT),
                     |ui| {
-                        ui.heading(format!(
+                        ui.heading(language.fill(
                             "Iteration {}",
-                            self.functions[current].current_iteration
+                            &[self.functions[current].current_iteration.to_string()],
                         ));
                     },
                 );
             });
-            ui.heading(
-                if self.functions[current].is_error
-                    || self.functions[current].x2.is_nan()
-                    || self.functions[current].x2.is_infinite()
-                {
-                    "Error happened!"
-                } else if self.functions[current].reached_eps {
-                    "Reached end!"
-                } else {
-                    ""
-                },
-            );
+            match self.functions[current].shown_error() {
+                Some(error) => {
+                    ui.heading(
+                        egui::RichText::new(
+                            language.fill("Error: {}", &[language.tr(error.title()).to_string()]),
+                        )
+                        .color(self.theme.red),
+                    );
+                    ui.label(language.error(error));
+                }
+                None => {
+                    let function: &Function = &self.functions[current];
+                    ui.horizontal(|ui| {
+                        match function.shown().last() {
+                            Some(last) if function.shown_reached_eps() => {
+                                ui.heading(language.tr("Reached end!"));
+                                let triggered: Vec<&str> = function
+                                    .stop
+                                    .triggered(
+                                        function.method,
+                                        last,
+                                        &function.analysis,
+                                        function.eps,
+                                    )
+                                    .iter()
+                                    .map(|rule| rule.name())
+                                    .collect();
+                                ui.label(language.fill(
+                                    "Stopped by {}",
+                                    &[triggered.join(language.tr(" and "))],
+                                ));
+                            }
+                            _ => {
+                                ui.heading("");
+                            }
+                        }
+                        if let Some(bound) = function.shown().last().and_then(|last| {
+                            function
+                                .analysis
+                                .error_estimate(function.method, last)
+                                .filter(|_| last.x_prev.is_finite())
+                        }) {
+                            ui.label(language.fill(
+                                "Error bound: |xₙ − x*| ≤ {}",
+                                &[language.scientific(bound, 3)],
+                            ));
+                        }
+                    });
+                }
+            }
         });
+        egui::SidePanel::right("Conditions").show(ctx, |ui| {
+            let analysis: &Analysis = &self.functions[current].analysis;
+            let function: &Function = &self.functions[current];
+            ui.label(language.fill(
+                "Domain of f: {}",
+                &[language.decimal(function.domain.to_string())],
+            ));
+            if !function.roots.is_empty() {
+                let roots: Vec<String> = function
+                    .roots
+                    .iter()
+                    .map(|root| language.plain(*root))
+                    .collect();
+                ui.label(language.fill("Expected roots: {}", &[roots.join("; ")]));
+                if let Some(last) = function.shown().last() {
+                    let distance: f64 = function
+                        .roots
+                        .iter()
+                        .map(|root| (last.x - root).abs())
+                        .fold(f64::INFINITY, f64::min);
+                    ui.label(language.fill(
+                        "|x − x*| = {} to the nearest one",
+                        &[language.scientific(distance, 2)],
+                    ));
+                }
+            }
+            ui.heading(language.tr("Conditions on [a, b]"));
+            for (holds, text) in [
+                (analysis.sign_change, "Sign change: f(a)·f(b) < 0"),
+                (analysis.defined, "f is defined on [a, b]"),
+                (analysis.monotonic, "Monotonic: f' keeps its sign"),
+                (analysis.convex, "Constant convexity: f'' keeps its sign"),
+            ] {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} {}",
+                        if holds { "✓" } else { "✗" },
+                        language.tr(text)
+                    ))
+                    .color(if holds {
+                        self.theme.green
+                    } else {
+                        self.theme.red
+                    }),
+                );
+            }
+            ui.separator();
+            ui.label(format!(
+                "f ∈ [{}; {}]",
+                language.fixed(analysis.f.min, 4),
+                language.fixed(analysis.f.max, 4)
+            ));
+            ui.label(format!(
+                "f' ∈ [{}; {}]",
+                language.fixed(analysis.d1.min, 4),
+                language.fixed(analysis.d1.max, 4)
+            ));
+            ui.label(format!(
+                "f'' ∈ [{}; {}]",
+                language.fixed(analysis.d2.min, 4),
+                language.fixed(analysis.d2.max, 4)
+            ));
+            ui.label(language.fill(
+                "m₁ = min|f'| = {}, M₁ = max|f'| = {}",
+                &[
+                    language.fixed(analysis.d1.abs_min(), 4),
+                    language.fixed(analysis.d1.abs_max(), 4),
+                ],
+            ));
+        });
+        egui::SidePanel::left("Explain")
+            .default_width(380.0)
+            .show_animated(ctx, self.explain_opened, |ui| {
+                let function: &Function = &self.functions[current];
+                // A row picked in the history, otherwise the iteration on the plot.
+                let shown: Option<usize> = function
+                    .selected_iteration
+                    .filter(|i| *i < function.position)
+                    .or(function.position.checked_sub(1));
+                // The error follows the last iteration, or stands alone when the first step failed.
+                let error: Option<&SolverError> = function
+                    .shown_error()
+                    .filter(|_| shown.is_none_or(|i| i + 1 == function.position));
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    match shown {
+                        Some(i) => {
+                            let iteration: &Iteration = &function.history[i];
+                            ui.heading(language.fill("Iteration {}", &[(i + 1).to_string()]));
+                            let mut paragraphs: Vec<explain::Paragraph> = explain::step(
+                                language,
+                                &function.expr,
+                                function.method,
+                                function.a,
+                                function.b,
+                                function.eps,
+                                iteration,
+                            );
+                            paragraphs.push(explain::stop_test(
+                                language,
+                                &function.stop,
+                                function.method,
+                                &function.analysis,
+                                function.eps,
+                                iteration,
+                            ));
+                            for paragraph in paragraphs {
+                                ui.separator();
+                                ui.strong(paragraph.caption);
+                                for formula in paragraph.formulas {
+                                    ui.label(formula);
+                                }
+                                let conclusion =
+                                    egui::RichText::new(paragraph.conclusion).italics();
+                                ui.label(if paragraph.warning {
+                                    conclusion.color(self.theme.red)
+                                } else {
+                                    conclusion
+                                });
+                            }
+                        }
+                        None if error.is_none() => {
+                            ui.label(language.tr("Make a step to see it explained"));
+                        }
+                        None => {}
+                    }
+                    if let Some(error) = error {
+                        ui.separator();
+                        ui.label(
+                            egui::RichText::new(
+                                language.fill(
+                                    "The next step failed: {}",
+                                    &[language.tr(error.title())],
+                                ),
+                            )
+                            .color(self.theme.red),
+                        );
+                        ui.label(language.error(error));
+                    }
+                });
+            });
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label("Choose your equation: ");
+                ui.label(language.fill(
+                    "Choose your equation ({}): ",
+                    &[&self.problem_set],
+                ));
                 ui.vertical(|ui| {
-                    ui.selectable_value(
-                        &mut self.current_function,
-                        CurrentFunction::First,
-                        format!("{} = 0", &self.functions[0].title),
-                    );
-                    ui.selectable_value(
-                        &mut self.current_function,
-                        CurrentFunction::Second,
-                        format!("{} = 0", &self.functions[1].title),
-                    );
-                    ui.selectable_value(
-                        &mut self.current_function,
-                        CurrentFunction::Third,
-                        format!("{} = 0", &self.functions[2].title),
-                    );
-                    ui.selectable_value(
-                        &mut self.current_function,
-                        CurrentFunction::Fourth,
-                        format!("{} = 0", &self.functions[3].title),
-                    );
+                    // The last equation stays, so that there is always one to show.
+                    let removable: bool = self.functions.len() > 1;
+                    for (i, function) in self.functions.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(
+                                &mut self.current_function,
+                                i,
+                                format!("{} = 0", &function.title),
+                            );
+                            if removable
+                                && !function.builtin
+                                && ui.add(egui::Button::new("✖").small()).clicked()
+                            {
+                                removed_function = Some(i);
+                            }
+                        });
+                    }
                 });
             });
             ui.horizontal(|ui| {
-                ui.label("Set data:");
-                let a: f64 = self.functions[current].temp_a.clone();
+                ui.label(language.tr("Choose your method: "));
+                let mut method: Method = self.functions[current].method;
+                ui.add_enabled_ui(!self.functions[current].busy(), |ui| {
+                    for candidate in self.functions[current].methods.iter() {
+                        ui.selectable_value(&mut method, *candidate, language.method(*candidate));
+                    }
+                });
+                if method != self.functions[current].method {
+                    let function: &Function = &self.functions[current];
+                    self.functions[current] =
+                        function.rebuild(function.a, function.b, function.eps, method);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(language.tr("Add equation:"));
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.new_equation)
+                        .font(egui::TextStyle::Monospace)
+                        .hint_text("x^3 - 2*sin(x) + exp(-x/2)"),
+                );
+                if response.changed() {
+                    self.new_equation_error = None;
+                }
+                ui.label("= 0");
+                if ui.add(egui::Button::new(language.tr("Add"))).clicked()
+                    || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+                {
+                    match expr::parse(self.new_equation.as_str()) {
+                        Ok(expr) => {
+                            self.functions
+                                .push(Function::user(self.new_equation.trim(), expr));
+                            self.current_function = self.functions.len() - 1;
+                            self.new_equation.clear();
+                            self.new_equation_error = None;
+                        }
+                        Err(error) => self.new_equation_error = Some(error),
+                    }
+                }
+            });
+            if let Some(error) = &self.new_equation_error {
+                let caret: String = format!(
+                    "{}^",
+                    " ".repeat(self.new_equation.chars().count().min(error.position))
+                );
+                ui.label(
+                    egui::RichText::new(format!("{}\n{}", self.new_equation, caret))
+                        .monospace()
+                        .color(self.theme.red),
+                );
+                ui.label(
+                    egui::RichText::new(language.fill(
+                        "Parse error at position {}: {}",
+                        &[
+                            (error.position + 1).to_string(),
+                            language.fill(error.message, &[&error.detail]),
+                        ],
+                    ))
+                    .color(self.theme.red),
+                );
+            }
+            ui.horizontal(|ui| {
+                ui.label(language.tr("Set data:"));
+                let a: f64 = self.functions[current].temp_a;
                 let decimals: usize = {
                     let mut i: usize = 0;
                     let mut dec: usize = 0;
@@ -288,15 +1403,18 @@ impl eframe::App for MathApp {
                     }
                 };
                 let speed: f64 = 0.01;
-                ui.add(
-                    egui::DragValue::new(&mut self.functions[current].temp_a)
+                let a_changed: bool = ui
+                    .add(
+                    language
+                        .drag_value(egui::DragValue::new(&mut self.functions[current].temp_a))
                         .speed(speed)
                         .min_decimals(decimals)
                         .max_decimals(decimals)
                         .update_while_editing(false)
                         .prefix("a: "),
-                );
-                let a: f64 = self.functions[current].temp_b.clone();
+                )
+                    .changed();
+                let a: f64 = self.functions[current].temp_b;
                 let decimals: usize = {
                     let mut i: usize = 0;
                     let mut dec: usize = 0;
@@ -310,15 +1428,18 @@ impl eframe::App for MathApp {
                     }
                 };
                 let speed: f64 = 0.01;
-                ui.add(
-                    egui::DragValue::new(&mut self.functions[current].temp_b)
+                let b_changed: bool = ui
+                    .add(
+                    language
+                        .drag_value(egui::DragValue::new(&mut self.functions[current].temp_b))
                         .speed(speed)
                         .min_decimals(decimals)
                         .max_decimals(decimals)
                         .update_while_editing(false)
                         .prefix("b: "),
-                );
-                let a: f64 = self.functions[current].temp_eps.clone();
+                )
+                    .changed();
+                let a: f64 = self.functions[current].temp_eps;
                 let decimals: usize = {
                     let mut i: usize = 0;
                     let mut dec: usize = 0;
@@ -333,7 +1454,8 @@ impl eframe::App for MathApp {
                 };
                 let speed: f64 = 0.01;
                 ui.add(
-                    egui::DragValue::new(&mut self.functions[current].temp_eps)
+                    language
+                        .drag_value(egui::DragValue::new(&mut self.functions[current].temp_eps))
                         .speed(speed)
                         .min_decimals(decimals)
                         .max_decimals(decimals)
@@ -341,234 +1463,1165 @@ impl eframe::App for MathApp {
                         .clamp_range(0..=i64::MAX)
                         .prefix("eps: "),
                 );
-                ui.label("Apply data:");
-                if ui.add(egui::Button::new("Update")).clicked() {
-                    let temp_eps: f64 = self.functions[current].temp_eps;
-                    self.functions[current] = Function::new(
-                        self.functions[current].title.as_str(),
-                        self.functions[current].temp_a,
-                        self.functions[current].temp_b,
-                        self.functions[current].temp_eps,
-                        self.functions[current].current_function,
+                // An end is moved into the domain once it is entered, not on every frame.
+                if a_changed || b_changed {
+                    self.functions[current].clamp_bracket();
+                }
+                ui.label(language.tr("Apply data:"));
+                if ui
+                    .add_enabled(
+                        !self.functions[current].busy(),
+                        egui::Button::new(language.tr("Update")),
+                    )
+                    .clicked()
+                {
+                    let function: &Function = &self.functions[current];
+                    self.functions[current] = function.rebuild(
+                        function.temp_a,
+                        function.temp_b,
+                        function.temp_eps,
+                        function.method,
+                    );
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(language.tr("Stop when:"));
+                let mut stop: StopCriteria = self.functions[current].stop.clone();
+                for rule in StopRule::ALL {
+                    let mut enabled: bool = stop.rules.contains(&rule);
+                    // At least one rule stays on, otherwise the run never stops.
+                    if ui.checkbox(&mut enabled, rule.name()).changed()
+                        && (enabled || stop.rules.len() > 1)
+                    {
+                        stop.rules.retain(|other| *other != rule);
+                        if enabled {
+                            stop.rules.push(rule);
+                        }
+                    }
+                }
+                ui.add(
+                    language
+                        .drag_value(egui::DragValue::new(&mut stop.delta))
+                        .speed(1e-6)
+                        .clamp_range(0.0..=f64::MAX)
+                        .prefix("δ: "),
+                );
+                egui::ComboBox::from_id_source("Stop rule combination")
+                    .selected_text(language.tr(if stop.require_all {
+                        "all of them"
+                    } else {
+                        "any of them"
+                    }))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut stop.require_all,
+                            false,
+                            language.tr("any of them"),
+                        );
+                        ui.selectable_value(
+                            &mut stop.require_all,
+                            true,
+                            language.tr("all of them"),
+                        );
+                    });
+                if stop != self.functions[current].stop {
+                    self.functions[current].set_stop(stop);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(language.tr("Initial approximation:"));
+                ui.add(
+                    language
+                        .drag_value(egui::DragValue::new(&mut self.functions[current].scan_from))
+                        .speed(0.1)
+                        .prefix(language.tr("from: ")),
+                );
+                ui.add(
+                    language
+                        .drag_value(egui::DragValue::new(&mut self.functions[current].scan_to))
+                        .speed(0.1)
+                        .prefix(language.tr("to: ")),
+                );
+                if ui.add(egui::Button::new(language.tr("Find roots"))).clicked() {
+                    let function: &Function = &self.functions[current];
+                    self.functions[current].brackets = analysis::find_brackets(
+                        &function.expr,
+                        f64::min(function.scan_from, function.scan_to),
+                        f64::max(function.scan_from, function.scan_to),
+                        200,
                     );
-                    self.functions[current].eps = temp_eps;
+                }
+                match &self.functions[current].scan {
+                    Some(scan) => {
+                        if ui.add(egui::Button::new(language.tr("Cancel"))).clicked() {
+                            scan.cancel();
+                        }
+                        ui.add(
+                            egui::ProgressBar::new(scan.done as f32 / scan.total.max(1) as f32)
+                                .desired_width(200.0)
+                                .text(language.fill(
+                                    "{} of {} brackets",
+                                    &[scan.done.to_string(), scan.total.to_string()],
+                                )),
+                        );
+                    }
+                    None => {
+                        if ui.add(egui::Button::new(language.tr("Find all roots"))).clicked() {
+                            self.functions[current].find_all_roots();
+                            self.all_roots_opened = true;
+                        }
+                    }
+                }
+                if !self.functions[current].all_roots.is_empty()
+                    && ui.add(egui::Button::new(language.tr("Clear roots"))).clicked()
+                {
+                    self.functions[current].all_roots.clear();
+                    self.functions[current].scan_cancelled = None;
                 }
             });
+            if !self.functions[current].brackets.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(language.tr("Click to use:"));
+                    for bracket in self.functions[current].brackets.clone() {
+                        let ends: [String; 2] = [
+                            language.fixed(bracket.a, 4),
+                            language.fixed(bracket.b, 4),
+                        ];
+                        let text: String = match bracket.kind {
+                            BracketKind::SignChange => language.fill("[{}; {}]", &ends),
+                            BracketKind::EvenMultiplicity => {
+                                language.fill("[{}; {}] even root?", &ends)
+                            }
+                        };
+                        if ui
+                            .add(egui::Button::new(text).small())
+                            .on_hover_text(language.tr(match bracket.kind {
+                                BracketKind::SignChange => "f changes sign on this interval",
+                                BracketKind::EvenMultiplicity => {
+                                    "|f| almost touches zero here without changing sign"
+                                }
+                            }))
+                            .clicked()
+                        {
+                            // Rounded outwards so the DragValues do not show float noise.
+                            self.functions[current].temp_a = (bracket.a * 1e6).floor() / 1e6;
+                            self.functions[current].temp_b = (bracket.b * 1e6).ceil() / 1e6;
+                        }
+                    }
+                });
+            }
             ui.separator();
             ui.vertical_centered(|ui| {
-                ui.label(format!(
+                ui.label(language.fill(
                     "a: {}, b: {}, eps: {}",
-                    self.functions[current].a,
-                    self.functions[current].b,
-                    self.functions[current].eps
+                    &[
+                        language.plain(self.functions[current].a),
+                        language.plain(self.functions[current].b),
+                        language.plain(self.functions[current].eps),
+                    ],
                 ));
+                if let Some(mismatch) = self.functions[current].derivative_mismatch {
+                    ui.label(
+                        egui::RichText::new(language.fill(
+                            "Warning: f{} at x = {} is {} but finite differences give {}",
+                            &[
+                                if mismatch.order == 1 { "'" } else { "''" }.to_string(),
+                                language.plain(mismatch.x),
+                                language.plain(mismatch.automatic),
+                                language.plain(mismatch.numeric),
+                            ],
+                        ))
+                        .color(self.theme.red),
+                    );
+                }
             });
-            if self.functions[current].current_iteration == -1 {
-                let points: usize = 100;
-                let left: f64 = self.functions[current].a;
-                let right: f64 = self.functions[current].b;
-                let mut graph: Vec<[f64; 2]> = vec![[0., 0.]; points];
-                let step: f64 = (right - left) / points as f64;
-                let mut x: f64 = left;
-                graph[0][0] = x;
-                graph[0][1] = self.functions[current].f(x);
-                x += step;
-                let mut plot_max_y: f64 = f64::abs(graph[0][1]);
-                for i in 1..points {
-                    graph[i][0] = x;
-                    graph[i][1] = self.functions[current].f(x);
-                    plot_max_y = if plot_max_y < f64::abs(graph[i][1]) {
-                        graph[i][1]
-                    } else {
-                        plot_max_y
-                    };
-                    x += step;
-                }
-                self.functions[current]
-                    .current_plot_vec
-                    .insert(String::from("function"), graph);
-                let border_left: Vec<[f64; 2]> = vec![
-                    [self.functions[current].a, -plot_max_y],
-                    [self.functions[current].a, plot_max_y],
-                ];
-                self.functions[current]
-                    .current_plot_vec
-                    .insert(String::from("Left border"), border_left);
-                let border_right: Vec<[f64; 2]> = vec![
-                    [self.functions[current].b, -plot_max_y],
-                    [self.functions[current].b, plot_max_y],
-                ];
-                self.functions[current]
-                    .current_plot_vec
-                    .insert(String::from("Right border"), border_right);
-                self.functions[current].plot_max_y = plot_max_y;
-                self.functions[current].current_iteration += 1;
+            self.functions[current].prepare_plot();
+            // Leaves room for the convergence summary, the timeline and the playback buttons.
+            let row: f32 = ui.text_style_height(&egui::TextStyle::Button)
+                + 2.0 * ui.spacing().button_padding.y
+                + ui.spacing().item_spacing.y;
+            let plots_height: f32 = f32::max(ui.available_height() - 3.0 * row - 16.0, 250.0);
+            let (a, b, height) = (
+                self.functions[current].a,
+                self.functions[current].b,
+                self.functions[current].plot_max_y,
+            );
+            let response = egui_plot::Plot::new(("My Plot", current))
+                .legend(egui_plot::Legend::default())
+                .height(0.65 * plots_height)
+                .include_x(a - 0.1 * (b - a))
+                .include_x(b + 0.1 * (b - a))
+                .include_y(-1.1 * height)
+                .include_y(1.1 * height)
+                .x_axis_formatter(move |x, digits, _| language.decimal(axis_tick(x, digits)))
+                .y_axis_formatter(move |y, digits, _| language.decimal(axis_tick(y, digits)))
+                .show(ui, |plot_ui| {
+                    self.functions[current]
+                        .scene(&self.theme, language, animation)
+                        .show(plot_ui);
+                    if self.quiz_opened {
+                        plot_ui.points(
+                            egui_plot::Points::new(vec![[self.quiz.guess, 0.0]])
+                                .radius(5.0)
+                                .shape(egui_plot::MarkerShape::Diamond)
+                                .color(self.theme.mauve)
+                                .name(language.tr("Your answer")),
+                        );
+                    }
+                });
+            // In the quiz a click on the plot is the guess of where the chord crosses the axis.
+            if self.quiz_opened && response.response.clicked() {
+                if let Some(position) = response.response.interact_pointer_pos() {
+                    self.quiz.guess = response.transform.value_from_position(position).x;
+                    self.quiz.clicked = true;
+                }
+            }
+            let bounds = response.transform.bounds();
+            self.plot_bounds = (bounds.min(), bounds.max());
+            // The curve covers the visible range and is sampled again after panning or zooming.
+            if self.functions[current].follow_view(bounds) {
+                ctx.request_repaint();
             }
-            egui_plot::Plot::new("My Plot")
+            let convergence: Convergence = analysis::convergence(self.functions[current].shown());
+            ui.horizontal(|ui| {
+                ui.label(language.tr("Convergence:"));
+                match (convergence.order, convergence.rate) {
+                    (Some(order), Some(rate)) => ui.label(language.fill(
+                        "empirical order p ≈ {}, rate constant C ≈ {} (eₙ₊₁ ≈ C·eₙᵖ)",
+                        &[language.fixed(order, 3), language.scientific(rate, 3)],
+                    )),
+                    (Some(order), None) => ui.label(
+                        language.fill("empirical order p ≈ {}", &[language.fixed(order, 3)]),
+                    ),
+                    _ => ui.label(language.tr("the order needs three steps |xₙ − xₙ₋₁|")),
+                };
+            });
+            // Values are plotted as log₁₀ so the axis is logarithmic.
+            let log_points = |points: &[[f64; 2]]| -> Vec<[f64; 2]> {
+                points
+                    .iter()
+                    .filter(|[_, y]| *y > 0.0 && y.is_finite())
+                    .map(|[n, y]| [*n, y.log10()])
+                    .collect()
+            };
+            egui_plot::Plot::new("Convergence")
                 .legend(egui_plot::Legend::default())
+                .height(0.35 * plots_height)
+                .y_axis_formatter(|y, _, _| {
+                    if y.fract() == 0.0 {
+                        format!("1e{}", y)
+                    } else {
+                        String::new()
+                    }
+                })
+                .label_formatter(move |name, point| {
+                    if name.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            "{}\nn = {}: {}",
+                            name,
+                            point.x.round(),
+                            language.scientific(10f64.powf(point.y), 3)
+                        )
+                    }
+                })
                 .show(ui, |plot_ui| {
-                    let mut is_x1_line: bool = true;
-                    for line in self.functions[current].lines.clone().into_iter() {
-                        if is_x1_line {
-                            plot_ui.line(
-                                egui_plot::Line::new(egui_plot::PlotPoints::from(line))
-                                    .color(self.theme.green)
-                                    .name("Showcase"),
-                            );
-                        } else {
-                            plot_ui.line(
-                                egui_plot::Line::new(egui_plot::PlotPoints::from(line))
-                                    .color(self.theme.teal)
-                                    .name("Showcase"),
-                            );
-                        };
-                        is_x1_line = !is_x1_line;
+                    for (points, color, name) in [
+                        (&convergence.steps, self.theme.teal, "|xₙ − xₙ₋₁|"),
+                        (&convergence.residuals, self.theme.peach, "|f(xₙ)|"),
+                    ] {
+                        plot_ui.line(
+                            egui_plot::Line::new(egui_plot::PlotPoints::from(log_points(points)))
+                                .color(color)
+                                .name(name),
+                        );
+                        plot_ui.points(
+                            egui_plot::Points::new(log_points(points))
+                                .radius(3.0)
+                                .color(color)
+                                .name(name),
+                        );
                     }
-                    plot_ui.line(
-                        egui_plot::Line::new(egui_plot::PlotPoints::from(
-                            match self.functions[current]
-                                .current_plot_vec
-                                .get("function")
-                            {
-                                Some(graph) => graph.clone(),
-                                None => vec![],
-                            },
+                });
+            ui.horizontal(|ui| {
+                let function: &mut Function = &mut self.functions[current];
+                let mut position: usize = function.position;
+                ui.add_enabled(
+                    !function.history.is_empty() && function.worker.is_none(),
+                    egui::Slider::new(&mut position, 0..=function.history.len())
+                        .text(language.tr("timeline")),
+                );
+                if position != function.position {
+                    self.playing = false;
+                    function.go_to(position);
+                }
+                let branch_eps: f64 = function.temp_eps;
+                if ui
+                    .add_enabled(
+                        function.worker.is_none()
+                            && function.position <= function.states.len()
+                            && (function.position < function.history.len()
+                                || branch_eps != function.eps),
+                        egui::Button::new(language.tr("Branch from here")),
+                    )
+                    .on_hover_text(language.tr(
+                        "Drops the later iterations and continues from this one with eps from \"Set data\"",
+                    ))
+                    .clicked()
+                {
+                    self.playing = false;
+                    function.branch(branch_eps);
+                }
+                if function.worker.is_none() && !function.replayed() {
+                    ui.label(
+                        egui::RichText::new(language.fill(
+                            "The saved run is reproduced up to position {}; branch there to continue",
+                            &[function.states.len().to_string()],
                         ))
-                        .color(self.theme.red)
-                        .name(self.functions[current].title.as_str()),
+                        .color(self.theme.peach),
                     );
-                    plot_ui.line(
-                        egui_plot::Line::new(egui_plot::PlotPoints::from(
-                            match self.functions[current]
-                                .current_plot_vec
-                                .get("Left border")
-                            {
-                                Some(border) => border.clone(),
-                                None => vec![],
-                            },
+                }
+            });
+            ui.horizontal_centered(|ui| {
+                if ui
+                    .add_enabled(
+                        self.functions[current].position > 0
+                            && self.functions[current].worker.is_none(),
+                        egui::Button::new(language.tr("Previous iteration")),
+                    )
+                    .clicked()
+                {
+                    self.playing = false;
+                    let position: usize = self.functions[current].position;
+                    self.functions[current].go_to(position - 1);
+                }
+                if ui
+                    .add_enabled(
+                        !self.quiz_opened,
+                        egui::Button::new(language.tr(if self.playing {
+                        "⏸ Pause"
+                    } else {
+                            "▶ Play"
+                        })),
+                    )
+                    .clicked()
+                {
+                    self.playing = !self.playing && self.functions[current].can_step();
+                }
+                if ui
+                    .add_enabled(
+                        !self.quiz_opened,
+                        egui::Button::new(language.tr("Next iteration")),
+                    )
+                    .clicked()
+                    && self.functions[current].can_step()
+                {
+                    self.playing = false;
+                    self.functions[current].step();
+                    self.last_step_time = now;
+                }
+                match &self.functions[current].worker {
+                    Some(worker) => {
+                        if ui.add(egui::Button::new(language.tr("Cancel"))).clicked() {
+                            worker.cancel();
+                        }
+                        ui.add(
+                            egui::ProgressBar::new(worker.fraction())
+                                .desired_width(200.0)
+                                .text(language.fill(
+                                    "{} iterations, {} s",
+                                    &[
+                                        worker.iterations.to_string(),
+                                        language.fixed(worker.elapsed().as_secs_f64(), 1),
+                                    ],
+                                )),
+                        );
+                    }
+                    None => {
+                        if ui
+                            .add_enabled(
+                                !self.quiz_opened,
+                                egui::Button::new(language.tr("Solve")),
+                            )
+                            .clicked()
+                        {
+                            self.playing = false;
+                            self.last_step_time = f64::NEG_INFINITY;
+                            self.functions[current].solve_in_background();
+                        }
+                    }
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.play_delay, 0.1..=3.0)
+                        .step_by(0.1)
+                        .custom_formatter(|delay, _| language.fixed(delay, 1))
+                        .custom_parser(|text| text.trim().replace(',', ".").parse::<f64>().ok())
+                        .suffix(language.tr(" s"))
+                        .text(language.tr("delay")),
+                );
+                let function: &mut Function = &mut self.functions[current];
+                let mut max_iterations: usize = function.max_iterations;
+                ui.add_enabled(
+                    function.worker.is_none(),
+                    egui::DragValue::new(&mut max_iterations)
+                        .clamp_range(1..=1_000_000)
+                        .prefix(language.tr("max iterations: ")),
+                );
+                if max_iterations != function.max_iterations {
+                    function.set_max_iterations(max_iterations);
+                }
+                let mut time_budget: f64 = function.time_budget;
+                ui.add_enabled(
+                    function.worker.is_none(),
+                    language
+                        .drag_value(egui::DragValue::new(&mut time_budget))
+                        .speed(0.1)
+                        .clamp_range(0.1..=600.0)
+                        .prefix(language.tr("time budget: "))
+                        .suffix(language.tr(" s")),
+                );
+                if time_budget != function.time_budget {
+                    function.set_time_budget(time_budget);
+                }
+            });
+        });
+        egui::Window::new(language.tr("All roots"))
+            .id(egui::Id::new("All roots"))
+            .open(&mut self.all_roots_opened)
+            .show(ctx, |ui| {
+                let function: &Function = &self.functions[current];
+                ui.label(language.fill(
+                    "{} = 0 on [{}; {}], method: {}, eps: {}",
+                    &[
+                        function.title.clone(),
+                        language.plain(f64::min(function.scan_from, function.scan_to)),
+                        language.plain(f64::max(function.scan_from, function.scan_to)),
+                        language.method(function.method).to_string(),
+                        language.plain(function.eps),
+                    ],
+                ));
+                if let Some((done, total)) = function.scan_cancelled {
+                    ui.label(
+                        egui::RichText::new(language.fill(
+                            "Cancelled after {} of {} brackets, the others are not solved",
+                            &[done.to_string(), total.to_string()],
                         ))
-                        .color(self.theme.pink)
-                        .name("Left and Right borders"),
+                        .color(self.theme.red),
                     );
-                    plot_ui.line(
-                        egui_plot::Line::new(egui_plot::PlotPoints::from(
-                            match self.functions[current]
-                                .current_plot_vec
-                                .get("Right border")
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("All roots table")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in ["#", "[a; b]", "x", "f(x)", "Iterations", "Result"] {
+                                ui.strong(language.tr(header));
+                            }
+                            ui.end_row();
+                            let palette = self.theme.palette();
+                            for (i, run) in function.all_roots.iter().enumerate() {
+                                ui.label(
+                                    egui::RichText::new(format!("{}", i + 1))
+                                        .color(palette[i % palette.len()]),
+                                );
+                                let bracket: String = format!(
+                                    "[{}; {}]",
+                                    language.fixed(run.a, 4),
+                                    language.fixed(run.b, 4)
+                                );
+                                // Brackets without a sign change are solved by Newton's method.
+                                if run.method == function.method {
+                                    ui.label(bracket);
+                                } else {
+                                    ui.label(format!(
+                                        "{} ({})",
+                                        bracket,
+                                        language.fill("{} method", &[language.method(run.method)])
+                                    ));
+                                }
+                                match run.iterations.last() {
+                                    Some(last) => {
+                                        ui.label(language.fixed(last.x, 8));
+                                        ui.label(language.scientific(last.fx, 2));
+                                    }
+                                    None => {
+                                        ui.label("—");
+                                        ui.label("—");
+                                    }
+                                }
+                                ui.label(format!("{}", run.iterations.len()));
+                                match &run.error {
+                                    Some(error) => {
+                                        ui.label(
+                                            egui::RichText::new(language.tr(error.title()))
+                                                .color(self.theme.red),
+                                        )
+                                        .on_hover_text(language.error(error));
+                                    }
+                                    None => {
+                                        ui.label(language.tr("Reached end!"));
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        egui::Window::new(language.tr("Iteration history"))
+            .id(egui::Id::new("Iteration history"))
+            .open(&mut self.history_opened)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                let function: &mut Function = &mut self.functions[current];
+                let orders: Vec<[f64; 2]> = analysis::convergence(function.shown()).orders;
+                let cell = |value: f64| -> String {
+                    if value.is_nan() {
+                        "—".to_string()
+                    } else {
+                        language.fixed(value, 8)
+                    }
+                };
+                egui::ScrollArea::both().show(ui, |ui| {
+                    egui::Grid::new("Iteration history table")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in [
+                                "n",
+                                "Fixed",
+                                "xₙ₋₁",
+                                "xₙ",
+                                "f(xₙ)",
+                                "|xₙ − xₙ₋₁|",
+                                "Order p",
+                                "Error estimate",
+                                "Stop",
+                            ] {
+                                ui.strong(language.tr(header));
+                            }
+                            ui.end_row();
+                            for (i, iteration) in
+                                function.history[..function.position].iter().enumerate()
                             {
-                                Some(border) => border.clone(),
-                                None => vec![],
-                            },
-                        ))
-                        .color(self.theme.pink)
-                        .name("Left and Right borders"),
+                                let is_selected: bool = function.selected_iteration == Some(i);
+                                if ui
+                                    .selectable_label(is_selected, format!("{}", iteration.n))
+                                    .clicked()
+                                {
+                                    function.selected_iteration =
+                                        if is_selected { None } else { Some(i) };
+                                }
+                                ui.label(cell(iteration.fixed.unwrap_or(f64::NAN)));
+                                ui.label(cell(iteration.x_prev));
+                                ui.label(cell(iteration.x));
+                                ui.label(language.scientific(iteration.fx, 3));
+                                let step: f64 = (iteration.x - iteration.x_prev).abs();
+                                ui.label(if step.is_nan() {
+                                    "—".to_string()
+                                } else {
+                                    language.scientific(step, 3)
+                                });
+                                ui.label(
+                                    match orders.iter().find(|[n, _]| *n == iteration.n as f64) {
+                                        Some([_, order]) => language.fixed(*order, 3),
+                                        None => "—".to_string(),
+                                    },
+                                );
+                                ui.label(
+                                    match function
+                                        .analysis
+                                        .error_estimate(function.method, iteration)
+                                    {
+                                        Some(estimate) if iteration.x_prev.is_finite() => {
+                                            language.scientific(estimate, 3)
+                                        }
+                                        _ => "—".to_string(),
+                                    },
+                                );
+                                ui.label(if function.stops(iteration) { "✓" } else { "" });
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        egui::Window::new(language.tr("Export"))
+            .id(egui::Id::new("Export"))
+            .open(&mut self.export_opened)
+            .show(ctx, |ui| {
+                let options: &mut ExportOptions = &mut self.export_options;
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("Format:"));
+                    for format in Format::ALL {
+                        if ui
+                            .selectable_value(&mut options.format, format, format.name())
+                            .clicked()
+                        {
+                            let stem: &str = match self.export_path.rsplit_once('.') {
+                                Some((stem, _)) => stem,
+                                None => self.export_path.as_str(),
+                            };
+                            self.export_path = format!("{}.{}", stem, format.extension());
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("Numbers:"));
+                    ui.selectable_value(&mut options.notation, Notation::Fixed, "0.001234");
+                    ui.selectable_value(&mut options.notation, Notation::Scientific, "1.234e-3");
+                    ui.add(
+                        egui::DragValue::new(&mut options.precision)
+                            .clamp_range(0..=16)
+                            .prefix(language.tr("digits: ")),
                     );
                 });
-            ui.horizontal_centered(|ui| {
-                if ui.add(egui::Button::new("Next iteration")).clicked()
-                    && !self.functions[current].reached_eps
-                    && !self.functions[current].is_error
-                    && !self.functions[current].x2.is_nan()
-                    && !self.functions[current].x2.is_infinite()
-                {
-                    match get_root_chord_method(&mut self.functions[current]) {
-                        Some(answer) => {
-                            self.functions[current].fixed = answer.fixed;
-                            self.functions[current].x2 = answer.x2;
-                            self.functions[current].reached_eps = answer.reached_eps;
-                            self.functions[current]
-                                .lines
-                                .append(&mut answer.lines.clone());
-                        }
-                        None => {
-                            self.functions[current].is_error = true;
-                        }
-                    }
-                    self.functions[current].current_iteration += 1;
-                }
-                if ui.add(egui::Button::new("Solve")).clicked()
-                    && !self.functions[current].reached_eps
-                    && !self.functions[current].is_error
-                    && !self.functions[current].x2.is_nan()
-                    && !self.functions[current].x2.is_infinite()
-                {
-                    loop {
-                        match get_root_chord_method(&mut self.functions[current]) {
-                            Some(answer) => {
-                                self.functions[current].fixed = answer.fixed;
-                                self.functions[current].x2 = answer.x2;
-                                self.functions[current].current_iteration += 1;
-                                self.functions[current]
-                                    .lines
-                                    .append(&mut answer.lines.clone());
-                                if answer.reached_eps {
-                                    self.functions[current].reached_eps =
-                                        answer.reached_eps;
-                                    break;
+                let text: String = export::render(&self.functions[current].report(), options);
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("File:"));
+                    ui.text_edit_singleline(&mut self.export_path);
+                    if ui.add(egui::Button::new(language.tr("Save"))).clicked() {
+                        self.export_status = match std::fs::write(&self.export_path, &text) {
+                            Ok(()) => language.fill("Saved to {}", &[&self.export_path]),
+                            Err(error) => language.fill("Could not save: {}", &[error.to_string()]),
+                        };
+                    }
+                    if ui
+                        .add(egui::Button::new(language.tr("Copy to clipboard")))
+                        .clicked()
+                    {
+                        ui.output_mut(|output| output.copied_text = text.clone());
+                        self.export_status = language.tr("Copied to clipboard").to_string();
+                    }
+                });
+                ui.label(&self.export_status);
+                ui.separator();
+                egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(text).monospace());
+                });
+            });
+        // The window saves the whole app, so it cannot hold on to one of its fields.
+        let mut session_opened: bool = self.session_opened;
+        egui::Window::new(language.tr("Session"))
+            .id(egui::Id::new("Session"))
+            .open(&mut session_opened)
+            .show(ctx, |ui| {
+                ui.label(language.tr(
+                    "Equations, a, b, eps, methods, stop rules, iterations, the theme and the language.",
+                ));
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("File:"));
+                    ui.text_edit_singleline(&mut self.session_path);
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(egui::Button::new(language.tr("Save session as…")))
+                        .clicked()
+                    {
+                        let text: String = session::to_json(&self.session());
+                        self.session_status = match std::fs::write(&self.session_path, text) {
+                            Ok(()) => language.fill("Saved to {}", &[&self.session_path]),
+                            Err(error) => language.fill("Could not save: {}", &[error.to_string()]),
+                        };
+                    }
+                    if ui
+                        .add(egui::Button::new(language.tr("Open session…")))
+                        .clicked()
+                    {
+                        match std::fs::read_to_string(&self.session_path)
+                            .map_err(|error| error.to_string())
+                            .and_then(|text| session::from_json(&text))
+                        {
+                            Ok(session) => opened_session = Some(session),
+                            Err(error) => {
+                                self.session_status = language.fill("Could not open: {}", &[error])
+                            }
+                        }
+                    }
+                });
+                if !self.session_status.is_empty() {
+                    ui.label(self.session_status.as_str());
+                }
+                ui.separator();
+                ui.label(language.fill("Problem set: {}", &[&self.problem_set]));
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("File:"));
+                    ui.text_edit_singleline(&mut self.problem_path);
+                    if ui
+                        .add(egui::Button::new(language.tr("Load problem set")))
+                        .on_hover_text(language.tr(
+                            "Replaces the equations of the current set, typed-in ones stay",
+                        ))
+                        .clicked()
+                    {
+                        match std::fs::read_to_string(&self.problem_path)
+                            .map_err(|error| error.to_string())
+                            .and_then(|text| problems::from_json(&text))
+                        {
+                            Ok(set) => opened_problems = Some(set),
+                            Err(error) => {
+                                self.session_status = language.fill("Could not load: {}", &[error])
+                            }
+                        }
+                    }
+                });
+            });
+        self.session_opened = session_opened;
+        egui::Window::new(language.tr("Compare methods"))
+            .id(egui::Id::new("Compare methods"))
+            .open(&mut self.compare_opened)
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(language.tr("Methods:"));
+                    for method in Method::ALL {
+                        let mut enabled: bool = self.compare_methods.contains(&method);
+                        let count: usize = self.compare_methods.len();
+                        if ui.checkbox(&mut enabled, language.method(method)).changed()
+                            && (if enabled { count < 4 } else { count > 2 })
+                        {
+                            self.compare_methods.retain(|other| *other != method);
+                            if enabled {
+                                self.compare_methods.push(method);
+                            }
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let function: &Function = &self.functions[current];
+                    if ui
+                        .add(egui::Button::new(language.tr("Start")))
+                        .on_hover_text(
+                            language.tr(
+                                "Runs the ticked methods on the current equation, a, b and eps",
+                            ),
+                        )
+                        .clicked()
+                    {
+                        if let Some(comparison) = &self.comparison {
+                            comparison.cancel();
+                        }
+                        self.comparison = Some(Comparison::new(function, &self.compare_methods));
+                    }
+                    let Some(comparison) = &mut self.comparison else {
+                        return;
+                    };
+                    if ui
+                        .add_enabled(
+                            comparison.can_step(),
+                            egui::Button::new(language.tr("Next iteration")),
+                        )
+                        .clicked()
+                    {
+                        comparison.step();
+                    }
+                    if comparison.running() {
+                        if ui.add(egui::Button::new(language.tr("Cancel"))).clicked() {
+                            comparison.cancel();
+                        }
+                        let (fraction, iterations, elapsed) = comparison.progress();
+                        ui.add(egui::ProgressBar::new(fraction).desired_width(200.0).text(
+                            language.fill(
+                                "{} iterations, {} s",
+                                &[
+                                    iterations.to_string(),
+                                    language.fixed(elapsed.as_secs_f64(), 1),
+                                ],
+                            ),
+                        ));
+                    } else if ui
+                        .add_enabled(
+                            comparison.can_step(),
+                            egui::Button::new(language.tr("Solve")),
+                        )
+                        .clicked()
+                    {
+                        // Every lane ends by a stop rule, an error, the iteration cap or the time budget.
+                        comparison.solve_in_background();
+                    }
+                    let lane: &Function = &comparison.lanes[0].function;
+                    ui.label(language.fill(
+                        "{} = 0 on [{}; {}], eps: {}",
+                        &[
+                            comparison.title.clone(),
+                            language.plain(lane.a),
+                            language.plain(lane.b),
+                            language.plain(lane.eps),
+                        ],
+                    ));
+                });
+                let Some(comparison) = &mut self.comparison else {
+                    return;
+                };
+                egui::Grid::new("Comparison plots").show(ui, |ui| {
+                    for (i, lane) in comparison.lanes.iter_mut().enumerate() {
+                        let function: &mut Function = &mut lane.function;
+                        function.prepare_plot();
+                        let (a, b, height) = (function.a, function.b, function.plot_max_y);
+                        ui.vertical(|ui| {
+                            ui.strong(language.method(function.method));
+                            let response = egui_plot::Plot::new(("Comparison plot", i))
+                                .width(380.0)
+                                .height(240.0)
+                                .include_x(a - 0.1 * (b - a))
+                                .include_x(b + 0.1 * (b - a))
+                                .include_y(-1.1 * height)
+                                .include_y(1.1 * height)
+                                .show(ui, |plot_ui| {
+                                    function.scene(&self.theme, language, 1.0).show(plot_ui);
+                                });
+                            if function.follow_view(response.transform.bounds()) {
+                                ctx.request_repaint();
+                            }
+                        });
+                        if i % 2 == 1 {
+                            ui.end_row();
+                        }
+                    }
+                });
+                ui.separator();
+                egui::Grid::new("Comparison summary")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in [
+                            "Method",
+                            "Iterations",
+                            "f evaluations",
+                            "x",
+                            "|x − x*|",
+                            "Time",
+                            "Result",
+                        ] {
+                            ui.strong(language.tr(header));
+                        }
+                        ui.end_row();
+                        for lane in comparison.lanes.iter() {
+                            let function: &Function = &lane.function;
+                            ui.label(language.method(function.method));
+                            ui.label(format!(
+                                "{}",
+                                function.history.last().map_or(0, |last| last.n)
+                            ));
+                            ui.label(format!(
+                                "{}",
+                                function
+                                    .history
+                                    .iter()
+                                    .map(|iteration| iteration.evaluations)
+                                    .sum::<usize>()
+                            ));
+                            match function.history.last() {
+                                Some(last) => {
+                                    ui.label(language.fixed(last.x, 10));
+                                    match comparison.root {
+                                        Some(root) => {
+                                            ui.label(language.scientific((last.x - root).abs(), 2))
+                                        }
+                                        None => ui.label("—"),
+                                    }
+                                    .on_hover_text(
+                                        language
+                                            .tr("x* is found by Brent's method with eps = 1e-15"),
+                                    );
+                                }
+                                None => {
+                                    ui.label("—");
+                                    ui.label("—");
                                 }
                             }
-                            None => {
-                                self.functions[current].is_error = true;
-                                break;
+                            ui.label(language.fill(
+                                "{} µs",
+                                &[language.fixed(lane.time.as_secs_f64() * 1e6, 1)],
+                            ));
+                            match &function.error {
+                                Some(error) => {
+                                    ui.label(
+                                        egui::RichText::new(language.tr(error.title()))
+                                            .color(self.theme.red),
+                                    )
+                                    .on_hover_text(language.error(error));
+                                }
+                                None if function.reached_eps => {
+                                    ui.label(language.tr("Reached end!"));
+                                }
+                                None => {
+                                    ui.label("…");
+                                }
                             }
+                            ui.end_row();
                         }
+                    });
+            });
+        egui::Window::new(language.tr("Export plot"))
+            .id(egui::Id::new("Export plot"))
+            .open(&mut self.plot_export_opened)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("Colors:"));
+                    ui.selectable_value(
+                        &mut self.plot_export_print,
+                        false,
+                        language.tr("Current theme"),
+                    );
+                    ui.selectable_value(
+                        &mut self.plot_export_print,
+                        true,
+                        language.tr("Print (white)"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("Size:"));
+                    ui.add(
+                        egui::DragValue::new(&mut self.plot_export_size[0])
+                            .clamp_range(200..=4000)
+                            .suffix(language.tr(" px")),
+                    );
+                    ui.label("×");
+                    ui.add(
+                        egui::DragValue::new(&mut self.plot_export_size[1])
+                            .clamp_range(150..=4000)
+                            .suffix(language.tr(" px")),
+                    );
+                    ui.add(
+                        language
+                            .drag_value(egui::DragValue::new(&mut self.plot_export_scale))
+                            .speed(0.1)
+                            .clamp_range(1.0..=4.0)
+                            .prefix(language.tr("PNG scale: ")),
+                    );
+                });
+                ui.label(language.fill(
+                    "Visible range: x ∈ [{}; {}], y ∈ [{}; {}]",
+                    &[
+                        language.fixed(self.plot_bounds.0[0], 4),
+                        language.fixed(self.plot_bounds.1[0], 4),
+                        language.fixed(self.plot_bounds.0[1], 4),
+                        language.fixed(self.plot_bounds.1[1], 4),
+                    ],
+                ));
+                let theme: Theme = if self.plot_export_print {
+                    scene::print_theme(&LATTE)
+                } else {
+                    self.theme
+                };
+                let svg = || {
+                    scene::to_svg(
+                        &self.functions[current].scene(&theme, language, 1.0),
+                        &theme,
+                        self.plot_bounds.0,
+                        self.plot_bounds.1,
+                        self.plot_export_size[0],
+                        self.plot_export_size[1],
+                    )
+                };
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("File:"));
+                    ui.text_edit_singleline(&mut self.plot_export_path);
+                    if ui.add(egui::Button::new(language.tr("Save SVG"))).clicked() {
+                        let path: String = format!("{}.svg", self.plot_export_path);
+                        self.plot_export_status = match std::fs::write(&path, svg()) {
+                            Ok(()) => language.fill("Saved to {}", &[path]),
+                            Err(error) => language.fill("Could not save: {}", &[error.to_string()]),
+                        };
+                    }
+                    if ui.add(egui::Button::new(language.tr("Save PNG"))).clicked() {
+                        let path: String = format!("{}.png", self.plot_export_path);
+                        self.plot_export_status =
+                            match scene::to_png(&svg(), self.plot_export_scale).and_then(|png| {
+                                std::fs::write(&path, png).map_err(|e| e.to_string())
+                            }) {
+                                Ok(()) => language.fill("Saved to {}", &[path]),
+                                Err(error) => language.fill("Could not save: {}", &[error]),
+                            };
                     }
+                });
+                ui.label(&self.plot_export_status);
+            });
+        egui::Window::new(language.tr("Quiz"))
+            .id(egui::Id::new("Quiz"))
+            .open(&mut self.quiz_opened)
+            .show(ctx, |ui| {
+                let quiz: &mut Quiz = &mut self.quiz;
+                let function: &mut Function = &mut self.functions[current];
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("Name:"));
+                    ui.text_edit_singleline(&mut quiz.student);
+                });
+                // The chord method starts from x₀, the others from x₁.
+                let next: usize = function.shown().last().map_or(
+                    if function.method == Method::Chord { 0 } else { 1 },
+                    |last| last.n + 1,
+                );
+                ui.label(language.fill(
+                    "What is {}? Type it in or click on the plot where the chord crosses the x axis.",
+                    &[explain::x(next)],
+                ));
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            language
+                                .drag_value(egui::DragValue::new(&mut quiz.guess))
+                                .speed(0.001)
+                                .prefix(format!("{} = ", explain::x(next))),
+                        )
+                        .changed()
+                    {
+                        quiz.clicked = false;
+                    }
+                    ui.add(
+                        language
+                            .drag_value(egui::DragValue::new(&mut quiz.tolerance))
+                            .speed(0.001)
+                            .clamp_range(0.0..=f64::INFINITY)
+                            .prefix(language.tr("accept within ±")),
+                    );
+                    if ui
+                        .add_enabled(
+                            function.can_step(),
+                            egui::Button::new(language.tr("Check")),
+                        )
+                        .clicked()
+                    {
+                        let position: usize = function.position;
+                        function.step();
+                        self.last_step_time = now;
+                        // A failed step reveals the error instead and is not scored.
+                        if function.position > position {
+                            let revealed: &Iteration = &function.history[function.position - 1];
+                            quiz.answer(&function.title, function.method, revealed.n, revealed.x);
+                        }
+                    }
+                });
+                if let Some(error) = function.shown_error() {
+                    ui.label(
+                        egui::RichText::new(language.fill(
+                            "The next step failed: {}",
+                            &[language.error(error)],
+                        ))
+                        .color(self.theme.red),
+                    );
                 }
+                if let Some(attempt) = quiz.attempts.last() {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(language.tr(if attempt.correct {
+                            "✓ Correct"
+                        } else {
+                            "✗ Wrong"
+                        }))
+                        .strong()
+                        .color(if attempt.correct {
+                            self.theme.green
+                        } else {
+                            self.theme.red
+                        }),
+                    );
+                    ui.label(language.fill(
+                        "{} = {}, your answer {}, error {}",
+                        &[
+                            explain::x(attempt.n),
+                            language.plain(attempt.answer),
+                            language.plain(attempt.guess),
+                            language.scientific((attempt.guess - attempt.answer).abs(), 2),
+                        ],
+                    ));
+                }
+                ui.strong(language.fill(
+                    "Score: {}/{}",
+                    &[quiz.correct().to_string(), quiz.attempts.len().to_string()],
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("Quiz table").striped(true).show(ui, |ui| {
+                            for header in ["Equation", "n", "Your answer", "xₙ", "Error", "Result"] {
+                                ui.strong(language.tr(header));
+                            }
+                            ui.end_row();
+                            for attempt in quiz.attempts.iter() {
+                                ui.label(&attempt.equation);
+                                ui.label(attempt.n.to_string());
+                                ui.label(language.plain(attempt.guess));
+                                ui.label(language.plain(attempt.answer));
+                                ui.label(
+                                    language.scientific((attempt.guess - attempt.answer).abs(), 2),
+                                );
+                                ui.label(language.tr(if attempt.correct { "✓" } else { "✗" }));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                ui.separator();
+                let text: String = quiz.summary();
+                ui.horizontal(|ui| {
+                    ui.label(language.tr("File:"));
+                    ui.text_edit_singleline(&mut self.quiz_path);
+                    if ui.add(egui::Button::new(language.tr("Save"))).clicked() {
+                        self.quiz_status = match std::fs::write(&self.quiz_path, &text) {
+                            Ok(()) => language.fill("Saved to {}", &[&self.quiz_path]),
+                            Err(error) => language.fill("Could not save: {}", &[error.to_string()]),
+                        };
+                    }
+                    if ui
+                        .add(egui::Button::new(language.tr("Copy to clipboard")))
+                        .clicked()
+                    {
+                        ui.output_mut(|output| output.copied_text = text.clone());
+                        self.quiz_status = language.tr("Copied to clipboard").to_string();
+                    }
+                    if ui.add(egui::Button::new(language.tr("Reset score"))).clicked() {
+                        quiz.attempts.clear();
+                        self.quiz_status.clear();
+                    }
+                });
+                ui.label(&self.quiz_status);
             });
-        });
-        egui::Window::new("Help") .open(&mut self.help_opened) .show(ctx, |ui| { ui.label("This program solves a nonlinear equation using the chord method."); ui.label("You can choose the equation by selecting it in a \"Choose your equation\" menu and set your data below."); ui.label("Then you can walk through iteration by clicking the \"Next iteration\" button or the \"Solve\" button."); ui.label("Each iteration will show on the graph how it finds each x closer to a real one.") });
-    }
-}
-struct Answer {
-    lines: Vec<Vec<[f64; 2]>>,
-    reached_eps: bool,
-    fixed: f64,
-    x2: f64,
-}
-fn get_root_chord_method(func: &Function) -> Option<Answer> {
-    let fixed: f64;
-    let x1: f64;
-    let x2: f64;
-    let mut answer: Answer = Answer {
-        lines: vec![],
-        reached_eps: false,
-        fixed: 0.0,
-        x2: 0.0,
-    };
-    if func.current_iteration == 0 {
-        if func.f(func.a) * func.f_der2(func.a) > 0.0 {
-            fixed = func.a;
-            // x1 = func.b;
-            // x2 = x1 - (func.f(x1) / (func.f(x1) - func.f(fixed))) * (x1 - fixed);
-            x2 = func.b;
-        } else {
-            fixed = func.b;
-            // x1 = func.a;
-            // x2 = x1 - (func.f(x1) / (func.f(fixed) - func.f(x1))) * (fixed - x1);
-            x2 = func.a;
+        egui::Window::new(language.tr("Help"))
+            .id(egui::Id::new("Help"))
+            .open(&mut self.help_opened)
+            .show(ctx, |ui| {
+                ui.label(language.tr("This program solves a nonlinear equation using the chord method."));
+                ui.label(language.tr("You can choose the equation by selecting it in a \"Choose your equation\" menu and set your data below."));
+                ui.label(language.tr("Then you can walk through iteration by clicking the \"Next iteration\" button or the \"Solve\" button."));
+                ui.label(language.tr("Each iteration will show on the graph how it finds each x closer to a real one."));
+                ui.collapsing(language.tr("Running the solver"), |ui| {
+                    ui.label(language.tr("\"Stop when\" picks the stop rules: the step, the rigorous bound (M₁ − m₁)/m₁·|xₙ − xₙ₋₁| for the chord method, |f(x)| < δ or a relative step."));
+                    ui.label(language.tr("\"Solve\" runs in the background up to the iteration cap and the time budget; \"Cancel\" keeps the iterations found so far."));
+                    ui.label(language.tr("\"Play\" steps on its own with the chosen delay, \"Pause\" stops it."));
+                    ui.label(language.tr("\"Previous iteration\" and the timeline go back to any step; \"Branch from here\" continues from it with a new eps."));
+                });
+                ui.collapsing(language.tr("Equations and domains"), |ui| {
+                    ui.label(language.tr("You can also type your own equation in the \"Add equation\" field, for example x^3 - 2*sin(x) + exp(-x/2)."));
+                    ui.label(language.tr("Supported: + - * / ^, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, exp, ln, lg, sqrt, abs and the constants pi and e."));
+                    ui.label(language.tr("Each equation has a domain, shaded on the graph; a and b are kept inside it, and the domain of a typed-in equation is found from ln, sqrt, division and powers."));
+                    ui.label(language.tr("The equations come from a problem set, a JSON file with titles, domains, brackets, eps, allowed methods and expected roots; \"Session\" loads another one."));
+                });
+                ui.collapsing(language.tr("Comparing methods"), |ui| {
+                    ui.label(language.tr("\"Compare\" runs two to four methods side by side on the same a, b and eps, steps them together and sums up iterations, function evaluations, errors and time."));
+                });
+                ui.collapsing(language.tr("Explanations and quizzes"), |ui| {
+                    ui.label(language.tr("\"Explain\" writes out the shown iteration: the sign check, the choice of the fixed endpoint, the chord formula with the numbers put in and the stopping test; a row picked in \"History\" is explained instead."));
+                    ui.label(language.tr("\"Quiz\" hides the next approximation until you type it in or click where the chord crosses the axis, then \"Check\" shows it with your error and the score; the results save as CSV for the teacher."));
+                });
+                ui.collapsing(language.tr("Sessions, files and language"), |ui| {
+                    ui.label(language.tr("The session is saved on exit and restored on launch; \"Session\" saves it to a file or opens one, for example a prepared scenario."));
+                    ui.label(language.tr("Without the window: opr solve --equation \"x^2 - 5*sin(x)\" --a 1.5 --b 3 --eps 1e-6 --method chord [--json], see opr solve --help."));
+                    ui.label(language.tr("The language button switches between English and Russian; Russian numbers use a decimal comma, exported files keep the point."));
+                    ui.label(language.tr("\"Export plot\" saves the visible part of the graph as SVG or PNG, in the current theme or in print colors."));
+                });
+            });
+        if let Some(i) = removed_function {
+            self.functions.remove(i);
+            if self.current_function >= i && self.current_function > 0 {
+                self.current_function -= 1;
+            }
         }
-    } else {
-        if func.f(func.a) * func.f_der2(func.a) > 0.0 {
-            fixed = func.fixed;
-            x1 = func.x2;
-            x2 = x1 - (func.f(x1) / (func.f(x1) - func.f(fixed))) * (x1 - fixed);
-        } else {
-            fixed = func.fixed;
-            x1 = func.x2;
-            x2 = x1 - (func.f(x1) / (func.f(fixed) - func.f(x1))) * (fixed - x1);
+        // Applied last: the panels above index the equations as they were at the start of the frame.
+        if let Some(set) = opened_problems {
+            self.session_status = language.fill("Loaded {}", &[&set.title]);
+            self.load_problem_set(set);
         }
-        if (x2 - x1).abs() <= func.eps {
-            answer.reached_eps = true;
+        if let Some(session) = opened_session {
+            self.session_status = match self.restore(session) {
+                Ok(()) => language.fill("Opened {}", &[&self.session_path]),
+                Err(error) => language.fill("Could not open: {}", &[error]),
+            };
         }
     }
-    if x2 > func.b || x2 < func.a {
-        return None;
-    }
-    answer.fixed = fixed;
-    answer.x2 = x2;
-    answer.lines = vec![
-        vec![[fixed, func.f(fixed)], [x2, func.f(x2)]],
-        vec![[x2, func.f(x2)], [x2, 0.0]],
-    ];
-    Some(answer)
 }
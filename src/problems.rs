@@ -0,0 +1,227 @@
+use crate::domain::{self, Domain};
+use crate::expr::{self, Expr};
+use crate::solver::Method;
+use serde_json::{Map, Value};
+
+/// Marks a file as a problem set of this program.
+const FORMAT: &str = "opr-problems";
+pub const VERSION: u64 = 1;
+
+/// The set the program starts with.
+pub const VARIANT_8: &str = include_str!("../problems/variant-8.json");
+
+/// One exercise: an equation with everything a teacher prepares for it.
+pub struct Problem {
+    pub title: String,
+    pub expr: Expr,
+    pub domain: Domain,
+    /// The suggested [a, b].
+    pub bracket: (f64, f64),
+    pub eps: f64,
+    /// The methods students may pick, the first one is selected.
+    pub methods: Vec<Method>,
+    /// The roots the answer is checked against.
+    pub roots: Vec<f64>,
+}
+
+pub struct ProblemSet {
+    pub title: String,
+    pub problems: Vec<Problem>,
+}
+
+fn problem(value: &Value) -> Result<Problem, String> {
+    let object: &Map<String, Value> = value.as_object().ok_or("a problem is not an object")?;
+    let text = |key: &str| -> Result<Option<&str>, String> {
+        match object.get(key) {
+            None => Ok(None),
+            Some(value) => value
+                .as_str()
+                .map(Some)
+                .ok_or_else(|| format!("\"{}\" must be a string", key)),
+        }
+    };
+    let numbers = |key: &str| -> Result<Option<Vec<f64>>, String> {
+        match object.get(key) {
+            None => Ok(None),
+            Some(value) => value
+                .as_array()
+                .and_then(|array| {
+                    array
+                        .iter()
+                        .map(Value::as_f64)
+                        .collect::<Option<Vec<f64>>>()
+                })
+                .map(Some)
+                .ok_or_else(|| format!("\"{}\" must be a list of numbers", key)),
+        }
+    };
+    let equation: &str = text("equation")?.ok_or("a problem has no \"equation\"")?;
+    let expr: Expr =
+        expr::parse(equation).map_err(|error| format!("\"{}\": {}", equation, error))?;
+    // Without a domain the problem gets the one a typed-in equation would.
+    let domain: Domain = match text("domain")? {
+        Some(domain) => domain
+            .parse::<Domain>()
+            .map_err(|error| format!("domain of \"{}\": {}", equation, error))?,
+        None => domain::infer(&expr),
+    };
+    let bracket: (f64, f64) = match numbers("bracket")?.as_deref() {
+        Some([a, b]) if a < b => domain.bracket(*a, *b),
+        Some(_) => {
+            return Err(format!(
+                "the bracket of \"{}\" must be [a, b] with a < b",
+                equation
+            ))
+        }
+        None => domain.bracket(-1.0, 1.0),
+    };
+    let methods: Vec<Method> = match object.get("methods") {
+        None => Method::ALL.to_vec(),
+        Some(value) => value
+            .as_array()
+            .ok_or("\"methods\" must be a list")?
+            .iter()
+            .map(|method| {
+                method
+                    .as_str()
+                    .and_then(Method::from_key)
+                    .ok_or_else(|| format!("unknown method {}", method))
+            })
+            .collect::<Result<Vec<Method>, String>>()?,
+    };
+    if methods.is_empty() {
+        return Err(format!("\"{}\" allows no methods", equation));
+    }
+    let eps: f64 = match object.get("eps") {
+        None => 0.001,
+        Some(value) => value
+            .as_f64()
+            .filter(|eps| *eps > 0.0)
+            .ok_or("\"eps\" must be a positive number")?,
+    };
+    Ok(Problem {
+        title: text("title")?.unwrap_or(equation).to_string(),
+        expr,
+        domain,
+        bracket,
+        eps,
+        methods,
+        roots: numbers("roots")?.unwrap_or_default(),
+    })
+}
+
+pub fn from_json(text: &str) -> Result<ProblemSet, String> {
+    let document: Value = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    let object: &Map<String, Value> = document.as_object().ok_or("not a problem set")?;
+    if object.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err("not a problem set".to_string());
+    }
+    let version: u64 = object
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or("\"version\" is missing")?;
+    if version > VERSION {
+        return Err(format!(
+            "the file has version {}, this program reads up to version {}",
+            version, VERSION
+        ));
+    }
+    let problems: Vec<Problem> = object
+        .get("problems")
+        .and_then(Value::as_array)
+        .ok_or("\"problems\" is missing")?
+        .iter()
+        .map(problem)
+        .collect::<Result<Vec<Problem>, String>>()?;
+    if problems.is_empty() {
+        return Err("the problem set is empty".to_string());
+    }
+    Ok(ProblemSet {
+        title: object
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or("Problems")
+            .to_string(),
+        problems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A problem set around the given `problems` list.
+    fn set(problems: &str) -> String {
+        format!(
+            "{{\"format\": \"opr-problems\", \"version\": 1, \"title\": \"Test\", \"problems\": {}}}",
+            problems
+        )
+    }
+
+    #[test]
+    fn built_in_set_loads() {
+        let set: ProblemSet = from_json(include_str!("../problems/variant-8.json")).unwrap();
+        assert_eq!(set.title, "Вариант 8");
+        assert_eq!(set.problems.len(), 4);
+        for problem in set.problems.iter() {
+            let (a, b) = problem.bracket;
+            assert!(a < b, "{}", problem.title);
+            assert_eq!(problem.domain.clamp(a), a, "{}", problem.title);
+            assert_eq!(problem.domain.clamp(b), b, "{}", problem.title);
+            assert!(!problem.methods.is_empty());
+            // The roots are given to four decimals.
+            for root in problem.roots.iter() {
+                let slope: f64 = problem.expr.eval_dual(*root).d1.abs().max(1.0);
+                assert!(
+                    problem.expr.eval(*root).abs() < 1e-3 * slope,
+                    "{}: f({}) = {}",
+                    problem.title,
+                    root,
+                    problem.expr.eval(*root)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        let set: ProblemSet = from_json(&set("[{\"equation\": \"ln(x) - 1\"}]")).unwrap();
+        let problem: &Problem = &set.problems[0];
+        assert_eq!(problem.title, "ln(x) - 1");
+        assert_eq!(problem.methods, Method::ALL.to_vec());
+        assert_eq!(problem.eps, 0.001);
+        // [-1, 1] is moved into the inferred domain x > 0.
+        assert!(problem.bracket.0 > 0.0);
+    }
+
+    #[test]
+    fn rejects_an_empty_set() {
+        assert_eq!(
+            from_json(&set("[]")).err().as_deref(),
+            Some("the problem set is empty")
+        );
+    }
+
+    #[test]
+    fn rejects_empty_methods() {
+        let text: String = set("[{\"equation\": \"x - 1\", \"methods\": []}]");
+        assert_eq!(
+            from_json(&text).err().as_deref(),
+            Some("\"x - 1\" allows no methods")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_problems() {
+        for problems in [
+            "[{\"equation\": \"x - 1\", \"methods\": [\"magic\"]}]",
+            "[{\"equation\": \"x - 1\", \"bracket\": [2, 1]}]",
+            "[{\"equation\": \"x - 1\", \"eps\": 0}]",
+            "[{\"equation\": \"x +\"}]",
+            "[{\"title\": \"no equation\"}]",
+        ] {
+            assert!(from_json(&set(problems)).is_err(), "{}", problems);
+        }
+        assert!(from_json("{\"format\": \"opr-session\"}").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::export::csv_field;
+use crate::solver::Method;
+
+/// One answer of the student and the iterate the method actually made.
+pub struct Attempt {
+    pub equation: String,
+    pub method: Method,
+    /// The index of the iterate that was asked for.
+    pub n: usize,
+    pub guess: f64,
+    pub answer: f64,
+    /// Whether the guess was clicked on the plot rather than typed in.
+    pub clicked: bool,
+    pub correct: bool,
+}
+
+/// A practice run: the student predicts every next iterate before it is shown.
+pub struct Quiz {
+    pub student: String,
+    /// How far a guess may be from the iterate and still count.
+    pub tolerance: f64,
+    pub guess: f64,
+    pub clicked: bool,
+    pub attempts: Vec<Attempt>,
+}
+
+impl Default for Quiz {
+    fn default() -> Quiz {
+        Quiz {
+            student: String::new(),
+            tolerance: 0.01,
+            guess: 0.0,
+            clicked: false,
+            attempts: vec![],
+        }
+    }
+}
+
+impl Quiz {
+    /// Scores the current guess against the iterate the method made.
+    pub fn answer(&mut self, equation: &str, method: Method, n: usize, answer: f64) {
+        self.attempts.push(Attempt {
+            equation: equation.to_string(),
+            method,
+            n,
+            guess: self.guess,
+            answer,
+            clicked: self.clicked,
+            correct: (self.guess - answer).abs() <= self.tolerance,
+        });
+    }
+    pub fn correct(&self) -> usize {
+        self.attempts
+            .iter()
+            .filter(|attempt| attempt.correct)
+            .count()
+    }
+    /// A CSV file for the teacher: who, the score, then one row per answer.
+    pub fn summary(&self) -> String {
+        let mut text: String = String::new();
+        for (key, value) in [
+            ("student", self.student.clone()),
+            (
+                "score",
+                format!("{}/{}", self.correct(), self.attempts.len()),
+            ),
+            ("tolerance", self.tolerance.to_string()),
+        ] {
+            text += &format!("{},{}\n", key, csv_field(&value));
+        }
+        text += "\nequation,method,n,guess,answer,error,result,input\n";
+        for attempt in self.attempts.iter() {
+            text += &[
+                csv_field(&format!("{} = 0", attempt.equation)),
+                csv_field(attempt.method.name()),
+                attempt.n.to_string(),
+                attempt.guess.to_string(),
+                attempt.answer.to_string(),
+                format!("{:.3e}", (attempt.guess - attempt.answer).abs()),
+                if attempt.correct { "correct" } else { "wrong" }.to_string(),
+                if attempt.clicked { "click" } else { "typed" }.to_string(),
+            ]
+            .join(",");
+            text += "\n";
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(quiz: &mut Quiz, guess: f64, clicked: bool, answer: f64) {
+        quiz.guess = guess;
+        quiz.clicked = clicked;
+        quiz.answer("x^2 - 2", Method::Chord, quiz.attempts.len() + 1, answer);
+    }
+
+    #[test]
+    fn guesses_within_the_tolerance_count() {
+        let mut quiz: Quiz = Quiz {
+            tolerance: 0.01,
+            ..Quiz::default()
+        };
+        guess(&mut quiz, 1.33, false, 1.3333);
+        guess(&mut quiz, 1.40, true, 1.4);
+        guess(&mut quiz, 1.45, false, 1.4118);
+        let correct: Vec<bool> = quiz
+            .attempts
+            .iter()
+            .map(|attempt| attempt.correct)
+            .collect();
+        assert_eq!(correct, [true, true, false]);
+        assert_eq!(quiz.correct(), 2);
+        assert!(quiz.attempts[1].clicked);
+    }
+
+    #[test]
+    fn summary_lists_the_score_and_every_answer() {
+        let mut quiz: Quiz = Quiz {
+            student: "Ivanov, I.".to_string(),
+            tolerance: 0.01,
+            ..Quiz::default()
+        };
+        guess(&mut quiz, 1.33, false, 1.3333);
+        guess(&mut quiz, 1.5, true, 1.4);
+        let summary: String = quiz.summary();
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "student,\"Ivanov, I.\"");
+        assert_eq!(lines[1], "score,1/2");
+        assert_eq!(lines[2], "tolerance,0.01");
+        assert_eq!(
+            lines[4],
+            "equation,method,n,guess,answer,error,result,input"
+        );
+        assert_eq!(
+            lines[5],
+            "x^2 - 2 = 0,Chord,1,1.33,1.3333,3.300e-3,correct,typed"
+        );
+        assert_eq!(lines[6], "x^2 - 2 = 0,Chord,2,1.5,1.4,1.000e-1,wrong,click");
+        assert_eq!(lines.len(), 7);
+    }
+}
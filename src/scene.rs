@@ -0,0 +1,578 @@
+use crate::colors::Theme;
+use crate::expr::Expr;
+use crate::locale;
+use eframe::egui::{self, Color32};
+use std::fmt::Write;
+
+pub struct SceneLine {
+    pub name: String,
+    pub color: Color32,
+    pub width: f32,
+    pub points: Vec<[f64; 2]>,
+}
+
+pub struct ScenePolygon {
+    pub name: String,
+    pub fill: Color32,
+    pub stroke: Option<Color32>,
+    pub points: Vec<[f64; 2]>,
+}
+
+pub struct ScenePoints {
+    pub name: String,
+    pub color: Color32,
+    pub radius: f32,
+    pub points: Vec<[f64; 2]>,
+}
+
+/// Everything drawn in the plot, in theme colors, so the same scene can be shown
+/// by `egui_plot` and exported to a file.
+#[derive(Default)]
+pub struct Scene {
+    pub polygons: Vec<ScenePolygon>,
+    pub lines: Vec<SceneLine>,
+    pub points: Vec<ScenePoints>,
+}
+
+impl Scene {
+    pub fn polygon(
+        &mut self,
+        name: &str,
+        fill: Color32,
+        stroke: Option<Color32>,
+        points: Vec<[f64; 2]>,
+    ) {
+        self.polygons.push(ScenePolygon {
+            name: name.to_string(),
+            fill,
+            stroke,
+            points,
+        });
+    }
+    pub fn line(&mut self, name: &str, color: Color32, width: f32, points: Vec<[f64; 2]>) {
+        self.lines.push(SceneLine {
+            name: name.to_string(),
+            color,
+            width,
+            points,
+        });
+    }
+    pub fn points(&mut self, name: &str, color: Color32, radius: f32, points: Vec<[f64; 2]>) {
+        self.points.push(ScenePoints {
+            name: name.to_string(),
+            color,
+            radius,
+            points,
+        });
+    }
+    pub fn show(&self, plot_ui: &mut egui_plot::PlotUi) {
+        for polygon in self.polygons.iter() {
+            plot_ui.polygon(
+                egui_plot::Polygon::new(egui_plot::PlotPoints::from(polygon.points.clone()))
+                    .fill_color(polygon.fill)
+                    .stroke(match polygon.stroke {
+                        Some(color) => egui::Stroke::new(1.0, color),
+                        None => egui::Stroke::NONE,
+                    })
+                    .name(&polygon.name),
+            );
+        }
+        for line in self.lines.iter() {
+            plot_ui.line(
+                egui_plot::Line::new(egui_plot::PlotPoints::from(line.points.clone()))
+                    .color(line.color)
+                    .width(line.width)
+                    .name(&line.name),
+            );
+        }
+        for points in self.points.iter() {
+            plot_ui.points(
+                egui_plot::Points::new(points.points.clone())
+                    .color(points.color)
+                    .radius(points.radius)
+                    .name(&points.name),
+            );
+        }
+    }
+}
+
+/// A palette for printed reports: the light theme on a white background.
+pub fn print_theme(theme: &Theme) -> Theme {
+    Theme {
+        base: Color32::WHITE,
+        mantle: Color32::WHITE,
+        crust: Color32::WHITE,
+        text: Color32::BLACK,
+        surface1: Color32::from_gray(225),
+        overlay1: Color32::from_gray(90),
+        ..*theme
+    }
+}
+
+fn svg_color(color: Color32) -> (String, f32) {
+    // `Color32` is premultiplied in gamma space, so undo it there too.
+    let alpha: f32 = color.a() as f32 / 255.0;
+    let channel = |value: u8| -> u8 {
+        if alpha > 0.0 {
+            (value as f32 / alpha).round().min(255.0) as u8
+        } else {
+            0
+        }
+    };
+    (
+        format!(
+            "rgb({},{},{})",
+            channel(color.r()),
+            channel(color.g()),
+            channel(color.b())
+        ),
+        alpha,
+    )
+}
+
+/// A round tick step that gives about `count` ticks on `range`, `None` when the range
+/// is empty or not finite and there is nothing to put ticks on.
+fn tick_step(range: f64, count: f64) -> Option<f64> {
+    let usable: bool = range.is_finite() && range > 0.0;
+    if !usable {
+        return None;
+    }
+    let raw: f64 = range / count;
+    let magnitude: f64 = 10f64.powf(raw.log10().floor());
+    let normalized: f64 = raw / magnitude;
+    let nice: f64 = if normalized < 1.5 {
+        1.0
+    } else if normalized < 3.5 {
+        2.0
+    } else if normalized < 7.5 {
+        5.0
+    } else {
+        10.0
+    };
+    Some(nice * magnitude).filter(|step| step.is_finite() && *step > 0.0)
+}
+
+fn tick_label(value: f64, step: f64) -> String {
+    let decimals: usize = if step >= 1.0 {
+        0
+    } else {
+        (-step.log10().floor()) as usize
+    };
+    // Accumulated steps land next to zero rather than on it.
+    let value: f64 = if value.abs() < 1e-9 * step {
+        0.0
+    } else {
+        value
+    };
+    format!("{:.1$}", value, decimals)
+}
+
+/// Renders the scene inside `min..max` (x and y ranges) to an SVG document.
+pub fn to_svg(
+    scene: &Scene,
+    theme: &Theme,
+    min: [f64; 2],
+    max: [f64; 2],
+    width: u32,
+    height: u32,
+) -> String {
+    let (left, right, top, bottom) = (60.0, 20.0, 20.0, 40.0);
+    let plot_width: f64 = width as f64 - left - right;
+    let plot_height: f64 = height as f64 - top - bottom;
+    let to_x = |x: f64| left + (x - min[0]) / (max[0] - min[0]) * plot_width;
+    let to_y = |y: f64| top + (max[1] - y) / (max[1] - min[1]) * plot_height;
+    let mut svg: String = String::new();
+    let (background, _) = svg_color(theme.base);
+    let (text, _) = svg_color(theme.text);
+    let (grid, _) = svg_color(theme.surface1);
+    let (axis, _) = svg_color(theme.overlay1);
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">",
+        w = width,
+        h = height
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        background
+    );
+    let _ = writeln!(
+        svg,
+        "<defs><clipPath id=\"plot\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath></defs>",
+        left, top, plot_width, plot_height
+    );
+
+    // Grid and tick labels.
+    for (axis_index, count) in [(0, plot_width / 80.0), (1, plot_height / 50.0)] {
+        let Some(step) = tick_step(max[axis_index] - min[axis_index], count.max(2.0)) else {
+            continue;
+        };
+        let first: f64 = (min[axis_index] / step).ceil();
+        let last: f64 = (max[axis_index] / step).floor();
+        // Counted in steps: next to a large value a small step would not move it.
+        let ticks: usize = if last >= first {
+            (last - first).min(1000.0) as usize + 1
+        } else {
+            0
+        };
+        for i in 0..ticks {
+            let value: f64 = (first + i as f64) * step;
+            let label: String = tick_label(value, step);
+            if axis_index == 0 {
+                let x: f64 = to_x(value);
+                let _ = writeln!(
+                    svg,
+                    "<line x1=\"{x:.2}\" y1=\"{}\" x2=\"{x:.2}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"1\"/>",
+                    top,
+                    top + plot_height,
+                    grid
+                );
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{x:.2}\" y=\"{}\" fill=\"{}\" text-anchor=\"middle\">{}</text>",
+                    top + plot_height + 16.0,
+                    text,
+                    label
+                );
+            } else {
+                let y: f64 = to_y(value);
+                let _ = writeln!(
+                    svg,
+                    "<line x1=\"{}\" y1=\"{y:.2}\" x2=\"{}\" y2=\"{y:.2}\" stroke=\"{}\" stroke-width=\"1\"/>",
+                    left,
+                    left + plot_width,
+                    grid
+                );
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{:.2}\" fill=\"{}\" text-anchor=\"end\">{}</text>",
+                    left - 6.0,
+                    y + 4.0,
+                    text,
+                    label
+                );
+            }
+        }
+    }
+    if min[1] <= 0.0 && 0.0 <= max[1] {
+        let y: f64 = to_y(0.0);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{y:.2}\" x2=\"{}\" y2=\"{y:.2}\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+            left,
+            left + plot_width,
+            axis
+        );
+    }
+    if min[0] <= 0.0 && 0.0 <= max[0] {
+        let x: f64 = to_x(0.0);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x:.2}\" y1=\"{}\" x2=\"{x:.2}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+            top,
+            top + plot_height,
+            axis
+        );
+    }
+
+    let _ = writeln!(svg, "<g clip-path=\"url(#plot)\">");
+    let coordinates = |points: &[[f64; 2]]| -> String {
+        points
+            .iter()
+            .map(|[x, y]| format!("{:.2},{:.2}", to_x(*x), to_y(*y)))
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    for polygon in scene.polygons.iter() {
+        let (fill, opacity) = svg_color(polygon.fill);
+        let stroke: String = match polygon.stroke {
+            Some(color) => format!("stroke=\"{}\" stroke-width=\"1\"", svg_color(color).0),
+            None => "stroke=\"none\"".to_string(),
+        };
+        let _ = writeln!(
+            svg,
+            "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{:.3}\" {}/>",
+            coordinates(&polygon.points),
+            fill,
+            opacity,
+            stroke
+        );
+    }
+    for line in scene.lines.iter() {
+        let (color, opacity) = svg_color(line.color);
+        // A NaN or an infinite value breaks the line instead of joining across it.
+        for segment in line
+            .points
+            .split(|[x, y]| !x.is_finite() || !y.is_finite())
+            .filter(|segment| segment.len() > 1)
+        {
+            let _ = writeln!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{}\" stroke-linejoin=\"round\"/>",
+                coordinates(segment),
+                color,
+                opacity,
+                line.width.max(1.0)
+            );
+        }
+    }
+    for points in scene.points.iter() {
+        let (color, _) = svg_color(points.color);
+        for [x, y] in points.points.iter() {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{}\" fill=\"{}\"/>",
+                to_x(*x),
+                to_y(*y),
+                points.radius,
+                color
+            );
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    // Legend: every name once, in the order it first appears.
+    let mut entries: Vec<(&str, Color32)> = vec![];
+    let items = scene
+        .polygons
+        .iter()
+        .map(|p| (p.name.as_str(), p.stroke.unwrap_or(p.fill)))
+        .chain(scene.lines.iter().map(|l| (l.name.as_str(), l.color)))
+        .chain(scene.points.iter().map(|p| (p.name.as_str(), p.color)));
+    for (name, color) in items {
+        if !name.is_empty() && !entries.iter().any(|(existing, _)| *existing == name) {
+            entries.push((name, color));
+        }
+    }
+    if !entries.is_empty() {
+        let longest: usize = entries
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let box_width: f64 = 34.0 + 7.0 * longest as f64;
+        let box_height: f64 = 8.0 + 18.0 * entries.len() as f64;
+        let x0: f64 = left + plot_width - box_width - 8.0;
+        let y0: f64 = top + 8.0;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" fill-opacity=\"0.85\" stroke=\"{}\"/>",
+            x0, y0, box_width, box_height, background, grid
+        );
+        for (i, (name, color)) in entries.iter().enumerate() {
+            let y: f64 = y0 + 16.0 + 18.0 * i as f64;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" stroke-width=\"3\"/>",
+                x0 + 6.0,
+                y - 4.0,
+                x0 + 24.0,
+                y - 4.0,
+                svg_color(*color).0
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.2}\" y=\"{:.2}\" fill=\"{}\">{}</text>",
+                x0 + 30.0,
+                y,
+                text,
+                xml_escape(name)
+            );
+        }
+    }
+    let _ = writeln!(svg, "</svg>");
+    svg
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The largest PNG side, so that an export never allocates a gigabyte-sized pixmap.
+const PNG_MAX_SIDE: u32 = 8000;
+
+/// Rasterises an SVG document `scale` times larger than its own size.
+pub fn to_png(svg: &str, scale: f32) -> Result<Vec<u8>, String> {
+    let mut options: resvg::usvg::Options = resvg::usvg::Options::default();
+    // The fonts of the window, so the labels render the same without a system font lookup.
+    let database = options.fontdb_mut();
+    for font in locale::fonts().font_data.values() {
+        database.load_font_data(font.font.to_vec());
+    }
+    let family: Option<String> = database
+        .faces()
+        .find(|face| {
+            face.families
+                .iter()
+                .any(|(name, _)| name.starts_with("Ubuntu"))
+        })
+        .map(|face| face.families[0].0.clone());
+    if let Some(family) = family {
+        database.set_sans_serif_family(family);
+    }
+    let tree: resvg::usvg::Tree =
+        resvg::usvg::Tree::from_str(svg, &options).map_err(|error| error.to_string())?;
+    let size = tree.size();
+    let width: u32 = (size.width() * scale).round() as u32;
+    let height: u32 = (size.height() * scale).round() as u32;
+    if width > PNG_MAX_SIDE || height > PNG_MAX_SIDE {
+        return Err(format!(
+            "A {}×{} image is over {} pixels a side",
+            width, height, PNG_MAX_SIDE
+        ));
+    }
+    let mut pixmap: resvg::tiny_skia::Pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| format!("Cannot create a {}×{} image", width, height))?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap.encode_png().map_err(|error| error.to_string())
+}
+
+/// Cells of the initial grid of `sample_curve`.
+const CURVE_CELLS: usize = 256;
+/// How many times a cell may be halved.
+const CURVE_DEPTH: usize = 12;
+/// Refinement stops once the curve has this many points.
+const CURVE_POINTS: usize = 20_000;
+
+/// Samples `f` on [from, to] for a view `height` units tall. Cells are halved where the
+/// curve bends, and the line is broken where `f` is undefined or jumps across the view.
+pub fn sample_curve(f: &Expr, from: f64, to: f64, height: f64) -> Vec<Vec<[f64; 2]>> {
+    let mut curve: Curve = Curve {
+        f,
+        tolerance: height / 2000.0,
+        jump: height,
+        segments: vec![],
+        points: 0,
+    };
+    let mut x0: f64 = from;
+    let mut y0: f64 = f.eval(x0);
+    curve.push(x0, y0);
+    for i in 1..=CURVE_CELLS {
+        let x1: f64 = from + (to - from) * i as f64 / CURVE_CELLS as f64;
+        let y1: f64 = f.eval(x1);
+        curve.refine(x0, y0, x1, y1, 0);
+        (x0, y0) = (x1, y1);
+    }
+    curve.segments.retain(|segment| segment.len() > 1);
+    curve.segments
+}
+
+struct Curve<'a> {
+    f: &'a Expr,
+    tolerance: f64,
+    jump: f64,
+    segments: Vec<Vec<[f64; 2]>>,
+    points: usize,
+}
+
+impl Curve<'_> {
+    /// Adds a point, or ends the current segment when `y` is not finite.
+    fn push(&mut self, x: f64, y: f64) {
+        if !y.is_finite() {
+            if self
+                .segments
+                .last()
+                .is_some_and(|segment| !segment.is_empty())
+            {
+                self.segments.push(vec![]);
+            }
+            return;
+        }
+        match self.segments.last_mut() {
+            Some(segment) => segment.push([x, y]),
+            None => self.segments.push(vec![[x, y]]),
+        }
+        self.points += 1;
+    }
+    /// Adds the points after `x0` up to and including `x1`.
+    fn refine(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, depth: usize) {
+        let xm: f64 = 0.5 * (x0 + x1);
+        let ym: f64 = self.f.eval(xm);
+        let can_split: bool = depth < CURVE_DEPTH && self.points < CURVE_POINTS;
+        let finite: usize = [y0, ym, y1].iter().filter(|y| y.is_finite()).count();
+        if finite == 3 {
+            if can_split && (ym - 0.5 * (y0 + y1)).abs() > self.tolerance {
+                self.refine(x0, y0, xm, ym, depth + 1);
+                self.refine(xm, ym, x1, y1, depth + 1);
+                return;
+            }
+            let jumps: bool = (y1 - y0).abs() > self.jump;
+            // Once the point cap stops the halving, a jump across zero with the middle
+            // outside the two ends is a pole too, as in tan(x) or 1/x.
+            let pole: bool =
+                y0 * y1 < 0.0 && (ym - y0) * (ym - y1) > 0.0 && self.points >= CURVE_POINTS;
+            if jumps && (depth >= CURVE_DEPTH || pole) {
+                // Still a jump across the whole view on the finest cell: a discontinuity.
+                self.push(xm, f64::NAN);
+            }
+        } else if finite > 0 && can_split {
+            // Locates where the domain of f begins or ends.
+            self.refine(x0, y0, xm, ym, depth + 1);
+            self.refine(xm, ym, x1, y1, depth + 1);
+            return;
+        }
+        self.push(x1, y1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::LATTE;
+
+    #[test]
+    fn tick_step_rejects_degenerate_ranges() {
+        assert_eq!(tick_step(0.0, 5.0), None);
+        assert_eq!(tick_step(-1.0, 5.0), None);
+        assert_eq!(tick_step(f64::NAN, 5.0), None);
+        assert_eq!(tick_step(f64::INFINITY, 5.0), None);
+        assert_eq!(tick_step(10.0, 5.0), Some(2.0));
+    }
+
+    #[test]
+    fn svg_export_finishes_on_a_flat_view() {
+        let mut scene: Scene = Scene::default();
+        scene.line("f", LATTE.blue, 2.0, vec![[1.0, 1.0], [1.0, 1.0]]);
+        let svg: String = to_svg(&scene, &LATTE, [1.0, 1.0], [1.0, 1.0], 800, 500);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn png_export_refuses_huge_images() {
+        let svg: String = to_svg(&Scene::default(), &LATTE, [0.0, 0.0], [1.0, 1.0], 4000, 500);
+        assert!(to_png(&svg, 4.0).is_err_and(|error| error.contains("16000×2000")));
+        let small: String = to_svg(&Scene::default(), &LATTE, [0.0, 0.0], [1.0, 1.0], 400, 250);
+        assert!(to_png(&small, 4.0).is_ok());
+    }
+
+    /// Refines [x0, x1] for `input` as if the point cap had already been reached and
+    /// returns how many pieces the line comes out in.
+    fn capped_pieces(input: &str, x0: f64, x1: f64) -> usize {
+        let f: Expr = crate::expr::parse(input).unwrap();
+        let mut curve: Curve = Curve {
+            f: &f,
+            tolerance: 0.005,
+            jump: 10.0,
+            segments: vec![],
+            points: CURVE_POINTS,
+        };
+        curve.push(x0, f.eval(x0));
+        curve.refine(x0, f.eval(x0), x1, f.eval(x1), 0);
+        curve.segments.len()
+    }
+
+    #[test]
+    fn curve_breaks_at_poles_after_the_cap() {
+        assert_eq!(capped_pieces("tan(x)", 1.5, 1.65), 2);
+        assert_eq!(capped_pieces("1/x", -0.01, 0.03), 2);
+        assert_eq!(capped_pieces("100*x", -1.0, 1.0), 1);
+    }
+}
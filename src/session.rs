@@ -0,0 +1,435 @@
+use crate::domain::{Domain, End, Interval};
+use crate::solver::{Iteration, LineKind, Method};
+use crate::stop::{StopCriteria, StopRule};
+use serde_json::{json, Map, Value};
+
+/// Marks a file as a session of this program.
+const FORMAT: &str = "opr-session";
+/// Raised whenever a field changes meaning; files of a newer version are refused.
+pub const VERSION: u64 = 1;
+
+/// One equation as saved: what was entered and how far the run went.
+pub struct SavedFunction {
+    pub title: String,
+    /// The equation as text that `expr::parse` reads back.
+    pub expression: String,
+    pub builtin: bool,
+    pub domain: Domain,
+    pub methods: Vec<Method>,
+    pub roots: Vec<f64>,
+    pub method: Method,
+    pub a: f64,
+    pub b: f64,
+    pub eps: f64,
+    pub temp_a: f64,
+    pub temp_b: f64,
+    pub temp_eps: f64,
+    pub stop: StopCriteria,
+    pub max_iterations: usize,
+    pub time_budget: f64,
+    pub scan_from: f64,
+    pub scan_to: f64,
+    /// The iterations made; the solver states are replayed from them on load.
+    pub history: Vec<Iteration>,
+    /// Where the timeline stood.
+    pub position: usize,
+}
+
+/// Everything that survives a restart or goes into a session file.
+pub struct Session {
+    pub problem_set: String,
+    pub theme: String,
+    /// A `Language::key`.
+    pub language: String,
+    pub current_function: usize,
+    pub functions: Vec<SavedFunction>,
+}
+
+pub fn to_json(session: &Session) -> String {
+    let functions: Vec<Value> = session
+        .functions
+        .iter()
+        .map(|function| {
+            json!({
+                "title": function.title,
+                "expression": function.expression,
+                "builtin": function.builtin,
+                "domain": domain_to_json(&function.domain),
+                "methods": function.methods.iter().map(Method::key).collect::<Vec<_>>(),
+                "roots": function.roots,
+                "method": function.method.key(),
+                "a": function.a,
+                "b": function.b,
+                "eps": function.eps,
+                "temp_a": function.temp_a,
+                "temp_b": function.temp_b,
+                "temp_eps": function.temp_eps,
+                "stop": {
+                    "rules": function.stop.rules.iter().map(|rule| rule.key()).collect::<Vec<_>>(),
+                    "require_all": function.stop.require_all,
+                    "delta": function.stop.delta,
+                },
+                "max_iterations": function.max_iterations,
+                "time_budget": function.time_budget,
+                "scan_from": function.scan_from,
+                "scan_to": function.scan_to,
+                "history": function.history.iter().map(iteration_to_json).collect::<Vec<_>>(),
+                "position": function.position,
+            })
+        })
+        .collect();
+    let document: Value = json!({
+        "format": FORMAT,
+        "version": VERSION,
+        "problem_set": session.problem_set,
+        "theme": session.theme,
+        "language": session.language,
+        "current_function": session.current_function,
+        "functions": functions,
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default() + "\n"
+}
+
+/// Intervals as `[lo, lo_closed, hi, hi_closed]`, with `null` for the infinite ends.
+fn domain_to_json(domain: &Domain) -> Value {
+    let value = |end: &End| -> Value {
+        if end.value.is_finite() {
+            json!(end.value)
+        } else {
+            Value::Null
+        }
+    };
+    domain
+        .intervals
+        .iter()
+        .map(|interval| {
+            json!([
+                value(&interval.lo),
+                interval.lo.closed,
+                value(&interval.hi),
+                interval.hi.closed
+            ])
+        })
+        .collect()
+}
+
+fn domain_from_json(value: &Value) -> Option<Domain> {
+    let end = |value: &Value, closed: &Value, infinity: f64| -> Option<End> {
+        Some(End {
+            value: if value.is_null() {
+                infinity
+            } else {
+                value.as_f64()?
+            },
+            closed: closed.as_bool()?,
+        })
+    };
+    let intervals: Vec<Interval> = value
+        .as_array()?
+        .iter()
+        .map(|interval| match interval.as_array()?.as_slice() {
+            [lo, lo_closed, hi, hi_closed] => Some(Interval {
+                lo: end(lo, lo_closed, f64::NEG_INFINITY)?,
+                hi: end(hi, hi_closed, f64::INFINITY)?,
+            }),
+            _ => None,
+        })
+        .collect::<Option<Vec<Interval>>>()?;
+    Some(Domain { intervals })
+}
+
+/// NaN and the infinities as `null`, which JSON has no numbers for.
+fn finite(value: f64) -> Value {
+    if value.is_finite() {
+        json!(value)
+    } else {
+        Value::Null
+    }
+}
+
+fn line_kind_key(kind: LineKind) -> &'static str {
+    match kind {
+        LineKind::Chord => "chord",
+        LineKind::Tangent => "tangent",
+        LineKind::Vertical => "vertical",
+    }
+}
+
+fn iteration_to_json(iteration: &Iteration) -> Value {
+    json!({
+        "n": iteration.n,
+        "x_prev": finite(iteration.x_prev),
+        "x": iteration.x,
+        "fx": iteration.fx,
+        "fixed": iteration.fixed,
+        "bracket": iteration.bracket.map(|(lo, hi)| [lo, hi]),
+        "lines": iteration
+            .lines
+            .iter()
+            .map(|(kind, points)| json!([line_kind_key(*kind), points]))
+            .collect::<Vec<_>>(),
+        "reached_eps": iteration.reached_eps,
+        "evaluations": iteration.evaluations,
+    })
+}
+
+fn iteration_from_json(value: &Value) -> Result<Iteration, String> {
+    let object: &Map<String, Value> = value.as_object().ok_or("an iteration is not an object")?;
+    let point = |value: &Value| -> Option<[f64; 2]> {
+        match value.as_array()?.as_slice() {
+            [x, y] => Some([x.as_f64()?, y.as_f64()?]),
+            _ => None,
+        }
+    };
+    let line = |value: &Value| -> Option<(LineKind, Vec<[f64; 2]>)> {
+        let [kind, points] = value.as_array()?.as_slice() else {
+            return None;
+        };
+        let kind: LineKind = [LineKind::Chord, LineKind::Tangent, LineKind::Vertical]
+            .into_iter()
+            .find(|known| Some(line_kind_key(*known)) == kind.as_str())?;
+        let points: Vec<[f64; 2]> = points
+            .as_array()?
+            .iter()
+            .map(point)
+            .collect::<Option<Vec<[f64; 2]>>>()?;
+        Some((kind, points))
+    };
+    Ok(Iteration {
+        n: count(object, "n")?,
+        x_prev: object
+            .get("x_prev")
+            .and_then(Value::as_f64)
+            .unwrap_or(f64::NAN),
+        x: number(object, "x")?,
+        fx: number(object, "fx")?,
+        fixed: object.get("fixed").and_then(Value::as_f64),
+        bracket: object
+            .get("bracket")
+            .and_then(point)
+            .map(|[lo, hi]| (lo, hi)),
+        lines: field(object, "lines", Value::as_array)?
+            .iter()
+            .map(|value| line(value).ok_or("a construction line is malformed"))
+            .collect::<Result<Vec<_>, &str>>()?,
+        reached_eps: field(object, "reached_eps", Value::as_bool)?,
+        evaluations: count(object, "evaluations")?,
+    })
+}
+
+fn method(value: &Value) -> Result<Method, String> {
+    value
+        .as_str()
+        .and_then(Method::from_key)
+        .ok_or_else(|| format!("unknown method {}", value))
+}
+
+/// Reads the field `key` of `object`, naming it in the error.
+fn field<'a, T>(
+    object: &'a Map<String, Value>,
+    key: &str,
+    read: impl Fn(&'a Value) -> Option<T>,
+) -> Result<T, String> {
+    object
+        .get(key)
+        .and_then(read)
+        .ok_or_else(|| format!("\"{}\" is missing or has a wrong type", key))
+}
+
+fn number(object: &Map<String, Value>, key: &str) -> Result<f64, String> {
+    field(object, key, Value::as_f64)
+}
+
+fn count(object: &Map<String, Value>, key: &str) -> Result<usize, String> {
+    field(object, key, |value| value.as_u64().map(|n| n as usize))
+}
+
+fn saved_function(value: &Value) -> Result<SavedFunction, String> {
+    let object: &Map<String, Value> = value.as_object().ok_or("a function is not an object")?;
+    let stop: &Map<String, Value> = field(object, "stop", Value::as_object)?;
+    let rules: Vec<StopRule> = field(stop, "rules", Value::as_array)?
+        .iter()
+        .map(|rule| {
+            StopRule::ALL
+                .into_iter()
+                .find(|known| Some(known.key()) == rule.as_str())
+                .ok_or_else(|| format!("unknown stop rule {}", rule))
+        })
+        .collect::<Result<Vec<StopRule>, String>>()?;
+    if rules.is_empty() {
+        return Err("a function has no stop rules".to_string());
+    }
+    Ok(SavedFunction {
+        title: field(object, "title", Value::as_str)?.to_string(),
+        expression: field(object, "expression", Value::as_str)?.to_string(),
+        builtin: field(object, "builtin", Value::as_bool)?,
+        domain: field(object, "domain", domain_from_json)?,
+        methods: field(object, "methods", Value::as_array)?
+            .iter()
+            .map(method)
+            .collect::<Result<Vec<Method>, String>>()?,
+        roots: field(object, "roots", Value::as_array)?
+            .iter()
+            .map(|root| root.as_f64().ok_or("a root is not a number"))
+            .collect::<Result<Vec<f64>, &str>>()?,
+        method: method(field(object, "method", Some)?)?,
+        a: number(object, "a")?,
+        b: number(object, "b")?,
+        eps: number(object, "eps")?,
+        temp_a: number(object, "temp_a")?,
+        temp_b: number(object, "temp_b")?,
+        temp_eps: number(object, "temp_eps")?,
+        stop: StopCriteria {
+            rules,
+            require_all: field(stop, "require_all", Value::as_bool)?,
+            delta: number(stop, "delta")?,
+        },
+        max_iterations: count(object, "max_iterations")?,
+        time_budget: number(object, "time_budget")?,
+        scan_from: number(object, "scan_from")?,
+        scan_to: number(object, "scan_to")?,
+        history: field(object, "history", Value::as_array)?
+            .iter()
+            .map(iteration_from_json)
+            .collect::<Result<Vec<Iteration>, String>>()?,
+        position: count(object, "position")?,
+    })
+}
+
+pub fn from_json(text: &str) -> Result<Session, String> {
+    let document: Value = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    let object: &Map<String, Value> = document.as_object().ok_or("not a session file")?;
+    if object.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err("not a session file".to_string());
+    }
+    let version: u64 = field(object, "version", Value::as_u64)?;
+    if version > VERSION {
+        return Err(format!(
+            "the file has version {}, this program reads up to version {}",
+            version, VERSION
+        ));
+    }
+    let functions: Vec<SavedFunction> = field(object, "functions", Value::as_array)?
+        .iter()
+        .map(saved_function)
+        .collect::<Result<Vec<SavedFunction>, String>>()?;
+    if functions.is_empty() {
+        return Err("the session has no equations".to_string());
+    }
+    Ok(Session {
+        problem_set: field(object, "problem_set", Value::as_str)?.to_string(),
+        theme: field(object, "theme", Value::as_str)?.to_string(),
+        language: field(object, "language", Value::as_str)?.to_string(),
+        current_function: count(object, "current_function")?.min(functions.len() - 1),
+        functions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+    use crate::solver::{self, Run};
+
+    fn saved_function(history: Vec<Iteration>) -> SavedFunction {
+        SavedFunction {
+            title: "x^2 - 5sin(x)".to_string(),
+            expression: "x^2 - 5*sin(x)".to_string(),
+            builtin: true,
+            domain: Domain::real_line(),
+            methods: vec![Method::Chord, Method::Newton],
+            roots: vec![0.0, 2.0],
+            method: Method::Chord,
+            a: 1.5,
+            b: 3.0,
+            eps: 1e-6,
+            temp_a: 1.5,
+            temp_b: 3.0,
+            temp_eps: 1e-4,
+            stop: StopCriteria {
+                rules: vec![StopRule::Step, StopRule::Residual],
+                require_all: true,
+                delta: 1e-9,
+            },
+            max_iterations: 50,
+            time_budget: 2.5,
+            scan_from: -10.0,
+            scan_to: 10.0,
+            position: 2,
+            history,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let run: Run = solver::solve(
+            Method::Chord,
+            &expr::parse("x^2 - 5*sin(x)").unwrap(),
+            1.5,
+            3.0,
+            1e-6,
+        );
+        let session: Session = Session {
+            problem_set: "Вариант 8".to_string(),
+            theme: "MOCHA".to_string(),
+            language: "ru".to_string(),
+            current_function: 7,
+            functions: vec![saved_function(run.iterations.clone())],
+        };
+        let loaded: Session = from_json(&to_json(&session)).unwrap();
+        assert_eq!(loaded.problem_set, session.problem_set);
+        assert_eq!(loaded.theme, session.theme);
+        assert_eq!(loaded.language, session.language);
+        // Past the end of the list, it points at the last equation.
+        assert_eq!(loaded.current_function, 0);
+        let function: &SavedFunction = &loaded.functions[0];
+        let expected: &SavedFunction = &session.functions[0];
+        assert_eq!(function.expression, expected.expression);
+        assert_eq!(function.methods, expected.methods);
+        assert_eq!(function.roots, expected.roots);
+        assert_eq!(function.stop, expected.stop);
+        assert_eq!(
+            (function.a, function.b, function.eps, function.temp_eps),
+            (expected.a, expected.b, expected.eps, expected.temp_eps)
+        );
+        assert_eq!(function.position, 2);
+        assert_eq!(function.history.len(), run.iterations.len());
+        for (loaded, saved) in function.history.iter().zip(run.iterations.iter()) {
+            assert_eq!(loaded.n, saved.n);
+            assert_eq!(loaded.x, saved.x);
+            assert_eq!(loaded.fx, saved.fx);
+            assert_eq!(loaded.fixed, saved.fixed);
+            assert_eq!(loaded.lines, saved.lines);
+            assert_eq!(loaded.reached_eps, saved.reached_eps);
+            assert_eq!(loaded.x_prev.is_nan(), saved.x_prev.is_nan());
+        }
+    }
+
+    #[test]
+    fn rejects_empty_and_foreign_files() {
+        let empty: Session = Session {
+            problem_set: String::new(),
+            theme: "LATTE".to_string(),
+            language: String::new(),
+            current_function: 0,
+            functions: vec![],
+        };
+        assert_eq!(
+            from_json(&to_json(&empty)).err().as_deref(),
+            Some("the session has no equations")
+        );
+        assert!(from_json("{\"format\": \"something else\"}").is_err());
+        assert!(from_json("not json").is_err());
+        let newer: String = to_json(&Session {
+            functions: vec![saved_function(vec![])],
+            ..empty
+        })
+        .replace(
+            &format!("\"version\": {}", VERSION),
+            &format!("\"version\": {}", VERSION + 1),
+        );
+        assert!(from_json(&newer)
+            .err()
+            .is_some_and(|error| error.contains("version")));
+    }
+}
@@ -0,0 +1,1161 @@
+use crate::expr::Expr;
+use std::fmt;
+
+/// By default a solver gives up with `SolverError::IterationLimit` after this many steps.
+pub const MAX_ITERATIONS: usize = 1000;
+
+/// Root-finding methods that can be selected in the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Chord,
+    Bisection,
+    Newton,
+    Secant,
+    RegulaFalsi,
+    Illinois,
+    Ridders,
+    Brent,
+    ChordTangent,
+}
+
+impl Method {
+    pub const ALL: [Method; 9] = [
+        Method::Chord,
+        Method::Bisection,
+        Method::Newton,
+        Method::Secant,
+        Method::RegulaFalsi,
+        Method::Illinois,
+        Method::Ridders,
+        Method::Brent,
+        Method::ChordTangent,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Chord => "Chord",
+            Method::Bisection => "Bisection",
+            Method::Newton => "Newton",
+            Method::Secant => "Secant",
+            Method::RegulaFalsi => "Regula falsi",
+            Method::Illinois => "Illinois",
+            Method::Ridders => "Ridders",
+            Method::Brent => "Brent",
+            Method::ChordTangent => "Chord–tangent",
+        }
+    }
+    /// The name in lower case with dashes, as typed on the command line.
+    pub fn key(&self) -> String {
+        self.name().to_lowercase().replace([' ', '–'], "-")
+    }
+    pub fn from_key(key: &str) -> Option<Method> {
+        Method::ALL.into_iter().find(|method| method.key() == key)
+    }
+    /// Whether the method starts from a bracket with `f(a)·f(b) < 0`.
+    pub fn needs_sign_change(&self) -> bool {
+        !matches!(self, Method::Newton | Method::Secant)
+    }
+    pub fn solver(&self, a: f64, b: f64, eps: f64) -> Box<dyn RootSolver> {
+        match self {
+            Method::Chord => Box::new(Chord::new(a, b, eps)),
+            Method::Bisection => Box::new(Bisection::new(a, b, eps)),
+            Method::Newton => Box::new(Newton::new(a, b, eps)),
+            Method::Secant => Box::new(Secant::new(a, b, eps)),
+            Method::RegulaFalsi => Box::new(FalsePosition::new(a, b, eps, false)),
+            Method::Illinois => Box::new(FalsePosition::new(a, b, eps, true)),
+            Method::Ridders => Box::new(Ridders::new(a, b, eps)),
+            Method::Brent => Box::new(Brent::new(a, b, eps)),
+            Method::ChordTangent => Box::new(ChordTangent::new(a, b, eps)),
+        }
+    }
+}
+
+/// How a construction line is drawn on the plot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Chord,
+    Tangent,
+    Vertical,
+}
+
+/// What a single step of a solver produced.
+#[derive(Debug, Clone)]
+pub struct Iteration {
+    pub n: usize,
+    pub x_prev: f64,
+    pub x: f64,
+    pub fx: f64,
+    /// The endpoint the chord method keeps fixed.
+    pub fixed: Option<f64>,
+    /// The bracket that still contains the root, for bracketing methods.
+    pub bracket: Option<(f64, f64)>,
+    /// Construction lines for the plot: the chord or tangent and the vertical to the curve.
+    pub lines: Vec<(LineKind, Vec<[f64; 2]>)>,
+    pub reached_eps: bool,
+    /// New values of f, f' or f'' the method needed for this step; values it already
+    /// knew from earlier steps and the safety checks of this program are not counted.
+    pub evaluations: usize,
+}
+
+/// Why a solver could not make the next step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolverError {
+    /// `f(a)` and `f(b)` have the same sign, so [a, b] need not contain a root.
+    NoSignChange { a: f64, b: f64, fa: f64, fb: f64 },
+    /// The new iterate is outside [a, b].
+    OutOfInterval { x: f64, a: f64, b: f64 },
+    /// `f''` at the new iterate has the opposite sign to `f''` at the fixed endpoint.
+    ConvexityChanged {
+        x: f64,
+        d2: f64,
+        fixed: f64,
+        d2_fixed: f64,
+    },
+    /// `f(x1) - f(other)` is too close to zero to draw a chord through both points.
+    FlatChord {
+        x1: f64,
+        other: f64,
+        denominator: f64,
+    },
+    /// `f'(x)` is too close to zero to draw a tangent.
+    FlatTangent { x: f64, derivative: f64 },
+    /// `f` is not defined (or not finite) at `x`.
+    Undefined { x: f64 },
+    /// The tolerance was not reached after `iterations` steps.
+    IterationLimit { iterations: usize, x: f64 },
+    /// The tolerance was not reached within the time budget of `seconds`.
+    TimeLimit {
+        seconds: f64,
+        iterations: usize,
+        x: f64,
+    },
+}
+
+impl SolverError {
+    /// The exit status of `opr solve` when a run ends with this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SolverError::NoSignChange { .. } => 10,
+            SolverError::OutOfInterval { .. } => 11,
+            SolverError::ConvexityChanged { .. } => 12,
+            SolverError::FlatChord { .. } => 13,
+            SolverError::FlatTangent { .. } => 14,
+            SolverError::Undefined { .. } => 15,
+            SolverError::IterationLimit { .. } => 16,
+            SolverError::TimeLimit { .. } => 17,
+        }
+    }
+    pub fn title(&self) -> &'static str {
+        match self {
+            SolverError::NoSignChange { .. } => "No sign change on [a, b]",
+            SolverError::OutOfInterval { .. } => "The iterate left the interval",
+            SolverError::ConvexityChanged { .. } => "f'' changes sign",
+            SolverError::FlatChord { .. } => "Division by a near-zero chord slope",
+            SolverError::FlatTangent { .. } => "Division by a near-zero derivative",
+            SolverError::Undefined { .. } => "f is undefined",
+            SolverError::IterationLimit { .. } => "Iteration limit reached",
+            SolverError::TimeLimit { .. } => "Time budget exceeded",
+        }
+    }
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SolverError::NoSignChange { a, b, fa, fb } => write!(
+                f,
+                "f(a) = f({}) = {} and f(b) = f({}) = {} have the same sign. \
+                 A continuous function is only guaranteed to have a root on [a, b] \
+                 when f(a)·f(b) < 0, so choose a narrower or a different interval.",
+                a, fa, b, fb
+            ),
+            SolverError::OutOfInterval { x, a, b } => write!(
+                f,
+                "The next approximation x = {} is outside [{}, {}]. \
+                 This happens when the convergence conditions do not hold on the interval: \
+                 f' or f'' change sign, or the root is not between a and b.",
+                x, a, b
+            ),
+            SolverError::ConvexityChanged {
+                x,
+                d2,
+                fixed,
+                d2_fixed,
+            } => write!(
+                f,
+                "f''({}) = {} but f''({}) = {} at the fixed endpoint. \
+                 The chord method needs f'' to keep one sign on [a, b], \
+                 otherwise the chords may jump over the root. Narrow the interval.",
+                x, d2, fixed, d2_fixed
+            ),
+            SolverError::FlatChord {
+                x1,
+                other,
+                denominator,
+            } => write!(
+                f,
+                "f({}) - f({}) = {} is almost zero, so the chord through these points \
+                 is nearly horizontal and never crosses the x axis near the root.",
+                x1, other, denominator
+            ),
+            SolverError::FlatTangent { x, derivative } => write!(
+                f,
+                "f'({}) = {} is almost zero, so the tangent is nearly horizontal \
+                 and crosses the x axis far away from the root.",
+                x, derivative
+            ),
+            SolverError::Undefined { x } => write!(
+                f,
+                "f({}) is not a finite number. The point is outside the domain of the function \
+                 (for example ln(x) with x ≤ 0, or division by zero). \
+                 Choose an interval where f is defined.",
+                x
+            ),
+            SolverError::IterationLimit { iterations, x } => write!(
+                f,
+                "The tolerance was not reached after {} iterations, the last approximation is x = {}. \
+                 The method converges too slowly on this interval or does not converge at all.",
+                iterations, x
+            ),
+            SolverError::TimeLimit {
+                seconds,
+                iterations,
+                x,
+            } => write!(
+                f,
+                "The tolerance was not reached within {} s ({} iterations), \
+                 the last approximation is x = {}. Raise the time budget or check the stop rules.",
+                seconds, iterations, x
+            ),
+        }
+    }
+}
+
+/// A root-finding method driven one iteration at a time.
+pub trait RootSolver: Send {
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError>;
+    /// A copy of the current state that can be stepped independently.
+    fn snapshot(&self) -> Box<dyn RootSolver>;
+    /// Changes the tolerance and the iteration cap for the following steps.
+    fn set_limits(&mut self, eps: f64, max_iterations: usize);
+}
+
+/// Shared bookkeeping: the interval, tolerance, iteration count and the last iterate.
+#[derive(Clone)]
+struct Progress {
+    a: f64,
+    b: f64,
+    eps: f64,
+    max_iterations: usize,
+    n: usize,
+    x: f64,
+}
+
+impl Progress {
+    fn new(a: f64, b: f64, eps: f64, x: f64) -> Progress {
+        Progress {
+            a,
+            b,
+            eps,
+            max_iterations: MAX_ITERATIONS,
+            n: 0,
+            x,
+        }
+    }
+    /// Checks the new iterate and builds the record, drawing `construction` and the vertical at `x`.
+    fn advance(
+        &mut self,
+        x: f64,
+        fx: f64,
+        construction: Vec<(LineKind, Vec<[f64; 2]>)>,
+        fixed: Option<f64>,
+        bracket: Option<(f64, f64)>,
+        evaluations: usize,
+    ) -> Result<Iteration, SolverError> {
+        if self.n >= self.max_iterations {
+            return Err(SolverError::IterationLimit {
+                iterations: self.n,
+                x: self.x,
+            });
+        }
+        if !x.is_finite() || x > self.b || x < self.a {
+            return Err(SolverError::OutOfInterval {
+                x,
+                a: self.a,
+                b: self.b,
+            });
+        }
+        if !fx.is_finite() {
+            return Err(SolverError::Undefined { x });
+        }
+        let x_prev: f64 = self.x;
+        self.n += 1;
+        self.x = x;
+        let mut lines: Vec<(LineKind, Vec<[f64; 2]>)> = construction;
+        lines.push((LineKind::Vertical, vec![[x, fx], [x, 0.0]]));
+        Ok(Iteration {
+            n: self.n,
+            x_prev,
+            x,
+            fx,
+            fixed,
+            bracket,
+            lines,
+            reached_eps: fx == 0.0 || (self.n > 1 && (x - x_prev).abs() <= self.eps),
+            evaluations,
+        })
+    }
+}
+
+fn same_sign(left: f64, right: f64) -> bool {
+    left * right > 0.0
+}
+
+/// `f(x)`, or `SolverError::Undefined` if it is NaN or infinite.
+fn value(f: &Expr, x: f64) -> Result<f64, SolverError> {
+    let fx: f64 = f.eval(x);
+    if fx.is_finite() {
+        Ok(fx)
+    } else {
+        Err(SolverError::Undefined { x })
+    }
+}
+
+/// `f(a)` and `f(b)`, checked to have opposite signs.
+fn sign_change(f: &Expr, a: f64, b: f64) -> Result<(f64, f64), SolverError> {
+    let fa: f64 = value(f, a)?;
+    let fb: f64 = value(f, b)?;
+    if same_sign(fa, fb) {
+        return Err(SolverError::NoSignChange { a, b, fa, fb });
+    }
+    Ok((fa, fb))
+}
+
+/// The x-intercept of the line through `(x1, f1)` and `(other, f_other)`.
+fn chord_root(x1: f64, f1: f64, other: f64, f_other: f64) -> Result<f64, SolverError> {
+    let denominator: f64 = f1 - f_other;
+    if denominator.abs() <= 1e-14 * (f1.abs() + f_other.abs()) || denominator == 0.0 {
+        return Err(SolverError::FlatChord {
+            x1,
+            other,
+            denominator,
+        });
+    }
+    Ok(x1 - f1 * (x1 - other) / denominator)
+}
+
+/// The x-intercept of the tangent at `x`.
+fn tangent_root(f: &Expr, x: f64) -> Result<f64, SolverError> {
+    let dual = f.eval_dual(x);
+    if !dual.value.is_finite() || !dual.d1.is_finite() {
+        return Err(SolverError::Undefined { x });
+    }
+    if dual.d1.abs() <= 1e-12 * f64::max(1.0, dual.value.abs()) {
+        return Err(SolverError::FlatTangent {
+            x,
+            derivative: dual.d1,
+        });
+    }
+    Ok(x - dual.value / dual.d1)
+}
+
+/// The classical chord method: one endpoint, chosen by `f(c) * f''(c) > 0`, stays fixed.
+#[derive(Clone)]
+pub struct Chord {
+    progress: Progress,
+    fixed: f64,
+}
+
+impl Chord {
+    fn new(a: f64, b: f64, eps: f64) -> Chord {
+        Chord {
+            progress: Progress::new(a, b, eps, b),
+            fixed: b,
+        }
+    }
+}
+
+/// The first call picks the fixed endpoint and returns the opposite one as x₀,
+/// every next call moves x along the chord to the fixed endpoint.
+pub fn get_root_chord_method(
+    f: &Expr,
+    a: f64,
+    b: f64,
+    fixed: f64,
+    x1: f64,
+    first: bool,
+) -> Result<(f64, f64), SolverError> {
+    if first {
+        let (fa, _) = sign_change(f, a, b)?;
+        return Ok(if fa * f.eval_dual(a).d2 > 0.0 {
+            (a, b)
+        } else {
+            (b, a)
+        });
+    }
+    if x1 == fixed {
+        // The last chord landed on the fixed endpoint, which is then the root.
+        return Ok((fixed, x1));
+    }
+    let x2: f64 = chord_root(x1, value(f, x1)?, fixed, value(f, fixed)?)?;
+    if !(a..=b).contains(&x2) {
+        return Err(SolverError::OutOfInterval { x: x2, a, b });
+    }
+    let d2: f64 = f.eval_dual(x2).d2;
+    let d2_fixed: f64 = f.eval_dual(fixed).d2;
+    if d2 * d2_fixed < 0.0 {
+        return Err(SolverError::ConvexityChanged {
+            x: x2,
+            d2,
+            fixed,
+            d2_fixed,
+        });
+    }
+    Ok((fixed, x2))
+}
+
+impl RootSolver for Chord {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let first: bool = self.progress.n == 0;
+        let (fixed, x2) = get_root_chord_method(
+            f,
+            self.progress.a,
+            self.progress.b,
+            self.fixed,
+            self.progress.x,
+            first,
+        )?;
+        self.fixed = fixed;
+        let fx: f64 = f.eval(x2);
+        let mut iteration: Iteration = self.progress.advance(
+            x2,
+            fx,
+            vec![(LineKind::Chord, vec![[fixed, f.eval(fixed)], [x2, fx]])],
+            Some(fixed),
+            None,
+            if first { 3 } else { 1 },
+        )?;
+        // The starting endpoint is iteration 0 and is never a stopping point.
+        iteration.n -= 1;
+        if first {
+            iteration.x_prev = f64::NAN;
+        }
+        iteration.reached_eps =
+            fx == 0.0 || (!first && (x2 - iteration.x_prev).abs() <= self.progress.eps);
+        Ok(iteration)
+    }
+}
+
+/// Halves the bracket every step.
+#[derive(Clone)]
+pub struct Bisection {
+    progress: Progress,
+    lo: f64,
+    hi: f64,
+    f_lo: Option<f64>,
+}
+
+impl Bisection {
+    fn new(a: f64, b: f64, eps: f64) -> Bisection {
+        Bisection {
+            progress: Progress::new(a, b, eps, b),
+            lo: a,
+            hi: b,
+            f_lo: None,
+        }
+    }
+}
+
+impl RootSolver for Bisection {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let first: bool = self.f_lo.is_none();
+        let f_lo: f64 = match self.f_lo {
+            Some(value) => value,
+            None => sign_change(f, self.lo, self.hi)?.0,
+        };
+        let mid: f64 = 0.5 * (self.lo + self.hi);
+        let f_mid: f64 = f.eval(mid);
+        if same_sign(f_lo, f_mid) {
+            self.lo = mid;
+            self.f_lo = Some(f_mid);
+        } else {
+            self.hi = mid;
+            self.f_lo = Some(f_lo);
+        }
+        self.progress.advance(
+            mid,
+            f_mid,
+            vec![],
+            None,
+            Some((self.lo, self.hi)),
+            if first { 3 } else { 1 },
+        )
+    }
+}
+
+/// Newton's method of tangents, started from the endpoint where `f(x) * f''(x) > 0`.
+#[derive(Clone)]
+pub struct Newton {
+    progress: Progress,
+}
+
+impl Newton {
+    fn new(a: f64, b: f64, eps: f64) -> Newton {
+        Newton {
+            progress: Progress::new(a, b, eps, f64::NAN),
+        }
+    }
+}
+
+impl RootSolver for Newton {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        // Every step needs f'(xₙ) and f(xₙ₊₁); the first one also f(a), f''(a) and maybe f(b).
+        let mut evaluations: usize = 2;
+        if self.progress.n == 0 && self.progress.x.is_nan() {
+            let a: f64 = self.progress.a;
+            self.progress.x = if value(f, a)? * f.eval_dual(a).d2 > 0.0 {
+                a
+            } else {
+                self.progress.b
+            };
+            evaluations += if self.progress.x == a { 2 } else { 3 };
+        }
+        let x1: f64 = self.progress.x;
+        let f1: f64 = value(f, x1)?;
+        let x2: f64 = tangent_root(f, x1)?;
+        let fx: f64 = f.eval(x2);
+        self.progress.advance(
+            x2,
+            fx,
+            vec![(LineKind::Tangent, vec![[x1, f1], [x2, 0.0]])],
+            None,
+            None,
+            evaluations,
+        )
+    }
+}
+
+/// The secant method: the chord through the two latest iterates.
+#[derive(Clone)]
+pub struct Secant {
+    progress: Progress,
+    x0: f64,
+}
+
+impl Secant {
+    fn new(a: f64, b: f64, eps: f64) -> Secant {
+        Secant {
+            progress: Progress::new(a, b, eps, b),
+            x0: a,
+        }
+    }
+}
+
+impl RootSolver for Secant {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let x0: f64 = self.x0;
+        let x1: f64 = self.progress.x;
+        let f0: f64 = value(f, x0)?;
+        let f1: f64 = value(f, x1)?;
+        let x2: f64 = chord_root(x1, f1, x0, f0)?;
+        let fx: f64 = f.eval(x2);
+        let iteration: Iteration = self.progress.advance(
+            x2,
+            fx,
+            vec![(LineKind::Chord, vec![[x0, f0], [x2, 0.0]])],
+            None,
+            None,
+            if self.progress.n == 0 { 3 } else { 1 },
+        )?;
+        self.x0 = x1;
+        Ok(iteration)
+    }
+}
+
+/// Regula falsi keeps a bracket; the Illinois variant halves the value
+/// of an endpoint that was retained twice in a row.
+#[derive(Clone)]
+pub struct FalsePosition {
+    progress: Progress,
+    illinois: bool,
+    lo: f64,
+    hi: f64,
+    f_lo: f64,
+    f_hi: f64,
+    /// -1 if the low endpoint was replaced last time, 1 for the high one.
+    last_side: i8,
+}
+
+impl FalsePosition {
+    fn new(a: f64, b: f64, eps: f64, illinois: bool) -> FalsePosition {
+        FalsePosition {
+            progress: Progress::new(a, b, eps, b),
+            illinois,
+            lo: a,
+            hi: b,
+            f_lo: f64::NAN,
+            f_hi: f64::NAN,
+            last_side: 0,
+        }
+    }
+}
+
+impl RootSolver for FalsePosition {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let first: bool = self.progress.n == 0;
+        if first {
+            (self.f_lo, self.f_hi) = sign_change(f, self.lo, self.hi)?;
+        }
+        let x: f64 = chord_root(self.hi, self.f_hi, self.lo, self.f_lo)?;
+        let fx: f64 = f.eval(x);
+        let construction: Vec<(LineKind, Vec<[f64; 2]>)> = vec![(
+            LineKind::Chord,
+            vec![[self.lo, self.f_lo], [self.hi, self.f_hi]],
+        )];
+        if same_sign(fx, self.f_lo) {
+            self.lo = x;
+            self.f_lo = fx;
+            if self.illinois && self.last_side == -1 {
+                self.f_hi *= 0.5;
+            }
+            self.last_side = -1;
+        } else {
+            self.hi = x;
+            self.f_hi = fx;
+            if self.illinois && self.last_side == 1 {
+                self.f_lo *= 0.5;
+            }
+            self.last_side = 1;
+        }
+        self.progress.advance(
+            x,
+            fx,
+            construction,
+            None,
+            Some((self.lo, self.hi)),
+            if first { 3 } else { 1 },
+        )
+    }
+}
+
+/// Ridders' method: an exponential fit through the bracket ends and its midpoint.
+#[derive(Clone)]
+pub struct Ridders {
+    progress: Progress,
+    lo: f64,
+    hi: f64,
+}
+
+impl Ridders {
+    fn new(a: f64, b: f64, eps: f64) -> Ridders {
+        Ridders {
+            progress: Progress::new(a, b, eps, b),
+            lo: a,
+            hi: b,
+        }
+    }
+}
+
+impl RootSolver for Ridders {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let (f_lo, f_hi) = sign_change(f, self.lo, self.hi)?;
+        let mid: f64 = 0.5 * (self.lo + self.hi);
+        let f_mid: f64 = value(f, mid)?;
+        let s: f64 = f64::sqrt(f_mid * f_mid - f_lo * f_hi);
+        let x: f64 = if s == 0.0 {
+            mid
+        } else {
+            mid + (mid - self.lo) * f64::signum(f_lo - f_hi) * f_mid / s
+        };
+        let fx: f64 = f.eval(x);
+        let construction: Vec<(LineKind, Vec<[f64; 2]>)> = vec![(
+            LineKind::Chord,
+            vec![[self.lo, f_lo], [mid, f_mid], [self.hi, f_hi]],
+        )];
+        if !same_sign(f_mid, fx) && f_mid != 0.0 {
+            self.lo = mid;
+            self.hi = x;
+        } else if !same_sign(f_lo, fx) {
+            self.hi = x;
+        } else {
+            self.lo = x;
+        }
+        let (lo, hi) = (f64::min(self.lo, self.hi), f64::max(self.lo, self.hi));
+        // The ends are earlier points, so only f at the midpoint and at x are new.
+        let evaluations: usize = if self.progress.n == 0 { 4 } else { 2 };
+        self.progress
+            .advance(x, fx, construction, None, Some((lo, hi)), evaluations)
+    }
+}
+
+/// Brent's method: inverse quadratic interpolation and secant steps with a bisection fallback.
+#[derive(Clone)]
+pub struct Brent {
+    progress: Progress,
+    a: f64,
+    b: f64,
+    c: f64,
+    fa: f64,
+    fb: f64,
+    fc: f64,
+    d: f64,
+    e: f64,
+}
+
+impl Brent {
+    fn new(a: f64, b: f64, eps: f64) -> Brent {
+        Brent {
+            progress: Progress::new(a, b, eps, b),
+            a,
+            b,
+            c: b,
+            fa: f64::NAN,
+            fb: f64::NAN,
+            fc: f64::NAN,
+            d: b - a,
+            e: b - a,
+        }
+    }
+}
+
+impl RootSolver for Brent {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let first: bool = self.progress.n == 0;
+        if first {
+            (self.fa, self.fb) = sign_change(f, self.a, self.b)?;
+            self.fc = self.fb;
+        }
+        if same_sign(self.fb, self.fc) {
+            self.c = self.a;
+            self.fc = self.fa;
+            self.d = self.b - self.a;
+            self.e = self.d;
+        }
+        if self.fc.abs() < self.fb.abs() {
+            self.a = self.b;
+            self.b = self.c;
+            self.c = self.a;
+            self.fa = self.fb;
+            self.fb = self.fc;
+            self.fc = self.fa;
+        }
+        let tol: f64 = 2.0 * f64::EPSILON * self.b.abs() + 0.5 * self.progress.eps;
+        let xm: f64 = 0.5 * (self.c - self.b);
+        if self.e.abs() >= tol && self.fa.abs() > self.fb.abs() {
+            let s: f64 = self.fb / self.fa;
+            let (mut p, mut q): (f64, f64) = if self.a == self.c {
+                (2.0 * xm * s, 1.0 - s)
+            } else {
+                let q: f64 = self.fa / self.fc;
+                let r: f64 = self.fb / self.fc;
+                (
+                    s * (2.0 * xm * q * (q - r) - (self.b - self.a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            let min1: f64 = 3.0 * xm * q - (tol * q).abs();
+            let min2: f64 = (self.e * q).abs();
+            if 2.0 * p < f64::min(min1, min2) {
+                self.e = self.d;
+                self.d = p / q;
+            } else {
+                self.d = xm;
+                self.e = self.d;
+            }
+        } else {
+            self.d = xm;
+            self.e = self.d;
+        }
+        let construction: Vec<(LineKind, Vec<[f64; 2]>)> =
+            vec![(LineKind::Chord, vec![[self.b, self.fb], [self.c, self.fc]])];
+        self.a = self.b;
+        self.fa = self.fb;
+        self.b += if self.d.abs() > tol {
+            self.d
+        } else {
+            tol * f64::signum(xm)
+        };
+        self.fb = f.eval(self.b);
+        let other: f64 = if same_sign(self.fb, self.fc) {
+            self.a
+        } else {
+            self.c
+        };
+        let (lo, hi) = (f64::min(self.b, other), f64::max(self.b, other));
+        self.progress.advance(
+            self.b,
+            self.fb,
+            construction,
+            None,
+            Some((lo, hi)),
+            if first { 3 } else { 1 },
+        )
+    }
+}
+
+/// The combined method: a chord from one end and a Newton tangent from the other,
+/// so the bracket [aₙ, bₙ] shrinks around the root from both sides.
+#[derive(Clone)]
+pub struct ChordTangent {
+    progress: Progress,
+    /// The end approached by tangents, where `f(x) * f''(x) > 0`.
+    tangent: f64,
+    /// The end approached by chords.
+    chord: f64,
+}
+
+impl ChordTangent {
+    fn new(a: f64, b: f64, eps: f64) -> ChordTangent {
+        ChordTangent {
+            progress: Progress::new(a, b, eps, f64::NAN),
+            tangent: f64::NAN,
+            chord: f64::NAN,
+        }
+    }
+}
+
+impl RootSolver for ChordTangent {
+    fn snapshot(&self) -> Box<dyn RootSolver> {
+        Box::new(self.clone())
+    }
+    fn set_limits(&mut self, eps: f64, max_iterations: usize) {
+        self.progress.eps = eps;
+        self.progress.max_iterations = max_iterations;
+    }
+    fn step(&mut self, f: &Expr) -> Result<Iteration, SolverError> {
+        let (a, b) = (self.progress.a, self.progress.b);
+        // f'(t), f(t_new) and f(c_new) every step, f(a), f(b) and f''(a) before the first one.
+        let evaluations: usize = if self.progress.n == 0 { 6 } else { 3 };
+        if self.progress.n == 0 {
+            let (fa, _) = sign_change(f, a, b)?;
+            (self.tangent, self.chord) = if fa * f.eval_dual(a).d2 > 0.0 {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            self.progress.x = 0.5 * (a + b);
+        }
+        let t: f64 = self.tangent;
+        let c: f64 = self.chord;
+        let f_t: f64 = value(f, t)?;
+        let f_c: f64 = value(f, c)?;
+        let t_new: f64 = tangent_root(f, t)?;
+        let c_new: f64 = chord_root(c, f_c, t, f_t)?;
+        for x in [t_new, c_new] {
+            if !(a..=b).contains(&x) {
+                return Err(SolverError::OutOfInterval { x, a, b });
+            }
+        }
+        self.tangent = t_new;
+        self.chord = c_new;
+        let (lo, hi) = (f64::min(t_new, c_new), f64::max(t_new, c_new));
+        let mid: f64 = 0.5 * (lo + hi);
+        let construction: Vec<(LineKind, Vec<[f64; 2]>)> = vec![
+            (LineKind::Chord, vec![[c, f_c], [t, f_t]]),
+            (LineKind::Tangent, vec![[t, f_t], [t_new, 0.0]]),
+            (
+                LineKind::Vertical,
+                vec![[c_new, f.eval(c_new)], [c_new, 0.0]],
+            ),
+        ];
+        let mut iteration: Iteration = self.progress.advance(
+            mid,
+            f.eval(mid),
+            construction,
+            None,
+            Some((lo, hi)),
+            evaluations,
+        )?;
+        iteration.reached_eps = hi - lo < self.progress.eps;
+        Ok(iteration)
+    }
+}
+
+/// A solver run from the first step until the tolerance is reached or the method fails.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub method: Method,
+    pub a: f64,
+    pub b: f64,
+    pub iterations: Vec<Iteration>,
+    pub error: Option<SolverError>,
+}
+
+impl Run {
+    /// The last approximation if the run reached the tolerance.
+    pub fn root(&self) -> Option<&Iteration> {
+        self.iterations
+            .last()
+            .filter(|iteration| iteration.reached_eps && self.error.is_none())
+    }
+}
+
+pub fn solve(method: Method, f: &Expr, a: f64, b: f64, eps: f64) -> Run {
+    let mut solver: Box<dyn RootSolver> = method.solver(a, b, eps);
+    let mut run: Run = Run {
+        method,
+        a,
+        b,
+        iterations: vec![],
+        error: None,
+    };
+    loop {
+        match solver.step(f) {
+            Ok(iteration) => {
+                let reached_eps: bool = iteration.reached_eps;
+                run.iterations.push(iteration);
+                if reached_eps {
+                    return run;
+                }
+            }
+            Err(error) => {
+                run.error = Some(error);
+                return run;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+
+    /// The real root of x³ − 2x − 5, Wallis's example.
+    const ROOT: f64 = 2.0945514815423265;
+
+    fn run(method: Method, text: &str, a: f64, b: f64, eps: f64) -> Run {
+        solve(method, &expr::parse(text).unwrap(), a, b, eps)
+    }
+
+    fn finds_root(method: Method) {
+        let run: Run = run(method, "x^3 - 2x - 5", 2.0, 3.0, 1e-10);
+        let root: &Iteration = run
+            .root()
+            .unwrap_or_else(|| panic!("{}: {:?}", method.name(), run.error));
+        assert!(
+            (root.x - ROOT).abs() < 1e-8,
+            "{}: x = {}",
+            method.name(),
+            root.x
+        );
+        assert!(run.iterations.len() < 100, "{}", method.name());
+    }
+
+    #[test]
+    fn chord() {
+        finds_root(Method::Chord);
+    }
+
+    #[test]
+    fn bisection() {
+        finds_root(Method::Bisection);
+    }
+
+    #[test]
+    fn newton() {
+        finds_root(Method::Newton);
+    }
+
+    #[test]
+    fn secant() {
+        finds_root(Method::Secant);
+    }
+
+    #[test]
+    fn regula_falsi() {
+        finds_root(Method::RegulaFalsi);
+    }
+
+    #[test]
+    fn illinois() {
+        finds_root(Method::Illinois);
+    }
+
+    #[test]
+    fn ridders() {
+        finds_root(Method::Ridders);
+    }
+
+    #[test]
+    fn brent() {
+        finds_root(Method::Brent);
+    }
+
+    #[test]
+    fn chord_tangent() {
+        finds_root(Method::ChordTangent);
+    }
+
+    #[test]
+    fn chord_starts_from_the_endpoint_it_does_not_fix() {
+        let run: Run = run(Method::Chord, "x^3 - 2x - 5", 2.0, 3.0, 1e-10);
+        // f(3)·f''(3) > 0, so b = 3 is fixed and x₀ = a = 2.
+        assert_eq!(run.iterations[0].n, 0);
+        assert_eq!(run.iterations[0].x, 2.0);
+        assert_eq!(run.iterations[0].fixed, Some(3.0));
+        assert!(!run.iterations[0].reached_eps);
+    }
+
+    #[test]
+    fn chord_stops_on_a_root_at_the_fixed_endpoint() {
+        // f(2)·f''(2) > 0 fixes b = 2, and the first chord from x₀ = 0 lands on it.
+        let run: Run = run(Method::Chord, "x^2 - 4", 0.0, 2.0, 1e-6);
+        assert!(run.error.is_none(), "{:?}", run.error);
+        let root: &Iteration = run.root().unwrap();
+        assert_eq!((root.n, root.x), (1, 2.0));
+        assert!(root.reached_eps);
+        let f: Expr = expr::parse("x^2 - 4").unwrap();
+        assert_eq!(
+            get_root_chord_method(&f, 0.0, 2.0, 2.0, 2.0, false),
+            Ok((2.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn no_sign_change() {
+        for method in [Method::Chord, Method::Bisection, Method::Brent] {
+            let run: Run = run(method, "x^2 + 1", -1.0, 2.0, 1e-6);
+            assert!(
+                matches!(run.error, Some(SolverError::NoSignChange { .. })),
+                "{}: {:?}",
+                method.name(),
+                run.error
+            );
+            assert!(run.root().is_none());
+        }
+    }
+
+    #[test]
+    fn iteration_limit() {
+        let mut solver: Box<dyn RootSolver> = Method::Bisection.solver(2.0, 3.0, 1e-12);
+        solver.set_limits(1e-12, 5);
+        let f: Expr = expr::parse("x^3 - 2x - 5").unwrap();
+        for _ in 0..5 {
+            solver.step(&f).unwrap();
+        }
+        assert!(matches!(
+            solver.step(&f),
+            Err(SolverError::IterationLimit { iterations: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn newton_diverges_out_of_the_interval() {
+        // From x₀ = 5 the tangent to atan overshoots to about −30.
+        let run: Run = run(Method::Newton, "atan(x)", -3.0, 5.0, 1e-6);
+        assert!(
+            matches!(run.error, Some(SolverError::OutOfInterval { x, .. }) if x < -3.0),
+            "{:?}",
+            run.error
+        );
+    }
+
+    #[test]
+    fn exit_codes_are_distinct_and_stable() {
+        let errors: [SolverError; 8] = [
+            SolverError::NoSignChange {
+                a: 0.0,
+                b: 1.0,
+                fa: 1.0,
+                fb: 1.0,
+            },
+            SolverError::OutOfInterval {
+                x: 2.0,
+                a: 0.0,
+                b: 1.0,
+            },
+            SolverError::ConvexityChanged {
+                x: 0.5,
+                d2: -1.0,
+                fixed: 1.0,
+                d2_fixed: 1.0,
+            },
+            SolverError::FlatChord {
+                x1: 0.0,
+                other: 1.0,
+                denominator: 0.0,
+            },
+            SolverError::FlatTangent {
+                x: 0.0,
+                derivative: 0.0,
+            },
+            SolverError::Undefined { x: 0.0 },
+            SolverError::IterationLimit {
+                iterations: 1000,
+                x: 0.5,
+            },
+            SolverError::TimeLimit {
+                seconds: 1.0,
+                iterations: 10,
+                x: 0.5,
+            },
+        ];
+        let codes: Vec<i32> = errors.iter().map(SolverError::exit_code).collect();
+        // `opr solve --help` documents these numbers; it has no time budget, so never 17.
+        assert_eq!(codes, vec![10, 11, 12, 13, 14, 15, 16, 17]);
+    }
+
+    #[test]
+    fn method_keys_round_trip() {
+        for method in Method::ALL {
+            assert_eq!(Method::from_key(&method.key()), Some(method));
+        }
+        assert_eq!(
+            Method::from_key("chord-tangent"),
+            Some(Method::ChordTangent)
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::analysis::Analysis;
+use crate::solver::{Iteration, Method};
+
+/// A test that ends the iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopRule {
+    /// The method's own step test, `|xₙ − xₙ₋₁| ≤ ε` for the chord method.
+    Step,
+    /// The a-posteriori bound `|xₙ − x*| ≤ (M₁ − m₁)/m₁ · |xₙ − xₙ₋₁| ≤ ε`.
+    Bound,
+    /// `|f(xₙ)| < δ`.
+    Residual,
+    /// `|xₙ − xₙ₋₁| ≤ ε·|xₙ|`.
+    Relative,
+}
+
+impl StopRule {
+    pub const ALL: [StopRule; 4] = [
+        StopRule::Step,
+        StopRule::Bound,
+        StopRule::Residual,
+        StopRule::Relative,
+    ];
+    /// A stable name for `--stop` and session files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            StopRule::Step => "step",
+            StopRule::Bound => "bound",
+            StopRule::Residual => "residual",
+            StopRule::Relative => "relative",
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            StopRule::Step => "|xₙ − xₙ₋₁| ≤ ε",
+            StopRule::Bound => "|xₙ − x*| ≤ ε",
+            StopRule::Residual => "|f(xₙ)| < δ",
+            StopRule::Relative => "|xₙ − xₙ₋₁| ≤ ε·|xₙ|",
+        }
+    }
+}
+
+/// Which rules stop the run and how they are combined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StopCriteria {
+    pub rules: Vec<StopRule>,
+    /// Stop when every rule holds instead of any of them.
+    pub require_all: bool,
+    pub delta: f64,
+}
+
+impl Default for StopCriteria {
+    fn default() -> StopCriteria {
+        StopCriteria {
+            rules: vec![StopRule::Step],
+            require_all: false,
+            delta: 1e-6,
+        }
+    }
+}
+
+impl StopCriteria {
+    pub fn holds(
+        &self,
+        rule: StopRule,
+        method: Method,
+        iteration: &Iteration,
+        analysis: &Analysis,
+        eps: f64,
+    ) -> bool {
+        let step: f64 = (iteration.x - iteration.x_prev).abs();
+        match rule {
+            StopRule::Step => iteration.reached_eps,
+            StopRule::Bound => analysis
+                .error_estimate(method, iteration)
+                .is_some_and(|bound| iteration.x_prev.is_finite() && bound <= eps),
+            StopRule::Residual => iteration.fx.abs() < self.delta,
+            StopRule::Relative => step <= eps * iteration.x.abs(),
+        }
+    }
+    /// The enabled rules that hold after `iteration`.
+    pub fn triggered(
+        &self,
+        method: Method,
+        iteration: &Iteration,
+        analysis: &Analysis,
+        eps: f64,
+    ) -> Vec<StopRule> {
+        self.rules
+            .iter()
+            .copied()
+            .filter(|rule| self.holds(*rule, method, iteration, analysis, eps))
+            .collect()
+    }
+    pub fn stops(
+        &self,
+        method: Method,
+        iteration: &Iteration,
+        analysis: &Analysis,
+        eps: f64,
+    ) -> bool {
+        let triggered: usize = self.triggered(method, iteration, analysis, eps).len();
+        if self.require_all {
+            triggered > 0 && triggered == self.rules.len()
+        } else {
+            triggered > 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis;
+    use crate::expr::{self, Expr};
+
+    /// x² − 2 on [1, 2]: m₁ = 2 and M₁ = 4, so the chord bound equals the step.
+    fn setup(x_prev: f64, x: f64) -> (Iteration, Analysis) {
+        let f: Expr = expr::parse("x^2 - 2").unwrap();
+        let iteration: Iteration = Iteration {
+            n: 1,
+            x_prev,
+            x,
+            fx: f.eval(x),
+            fixed: Some(2.0),
+            bracket: None,
+            lines: vec![],
+            reached_eps: false,
+            evaluations: 1,
+        };
+        (iteration, analysis::analyze(&f, 1.0, 2.0, 1000))
+    }
+
+    fn criteria(rules: &[StopRule], require_all: bool) -> StopCriteria {
+        StopCriteria {
+            rules: rules.to_vec(),
+            require_all,
+            delta: 1e-3,
+        }
+    }
+
+    #[test]
+    fn each_rule_has_its_own_test() {
+        let (mut iteration, analysis) = setup(1.5, 1.414);
+        let stop: StopCriteria = criteria(&StopRule::ALL, false);
+        let holds = |rule: StopRule, iteration: &Iteration, eps: f64| {
+            stop.holds(rule, Method::Chord, iteration, &analysis, eps)
+        };
+        // The step rule is the solver's own verdict.
+        assert!(!holds(StopRule::Step, &iteration, 1.0));
+        iteration.reached_eps = true;
+        assert!(holds(StopRule::Step, &iteration, 0.0));
+        // |f(1.414)| ≈ 6·10⁻⁴ < δ whatever eps is.
+        assert!(holds(StopRule::Residual, &iteration, 0.0));
+        // The step is 0.086: within 0.1·|x| = 0.141 but not 0.01·|x|.
+        assert!(holds(StopRule::Relative, &iteration, 0.1));
+        assert!(!holds(StopRule::Relative, &iteration, 0.01));
+        // The bound (M₁ − m₁)/m₁·0.086 is about 0.086 too.
+        assert!(holds(StopRule::Bound, &iteration, 0.1));
+        assert!(!holds(StopRule::Bound, &iteration, 0.05));
+    }
+
+    #[test]
+    fn bound_needs_a_previous_iterate() {
+        let (iteration, analysis) = setup(f64::NAN, 1.0);
+        let stop: StopCriteria = criteria(&[StopRule::Bound], false);
+        assert!(!stop.holds(StopRule::Bound, Method::Chord, &iteration, &analysis, 1e9));
+        assert!(!stop.stops(Method::Chord, &iteration, &analysis, 1e9));
+    }
+
+    #[test]
+    fn any_or_all_of_the_rules() {
+        let (iteration, analysis) = setup(1.5, 1.414);
+        let rules: [StopRule; 2] = [StopRule::Residual, StopRule::Relative];
+        let any: StopCriteria = criteria(&rules, false);
+        let all: StopCriteria = criteria(&rules, true);
+        assert_eq!(
+            any.triggered(Method::Chord, &iteration, &analysis, 0.01),
+            vec![StopRule::Residual]
+        );
+        assert!(any.stops(Method::Chord, &iteration, &analysis, 0.01));
+        assert!(!all.stops(Method::Chord, &iteration, &analysis, 0.01));
+        assert!(all.stops(Method::Chord, &iteration, &analysis, 0.1));
+        // No rules at all never stop, in either mode.
+        for require_all in [false, true] {
+            let none: StopCriteria = criteria(&[], require_all);
+            assert!(!none.stops(Method::Chord, &iteration, &analysis, 1e9));
+        }
+    }
+}
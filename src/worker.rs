@@ -0,0 +1,324 @@
+use crate::analysis::{self, Analysis, Bracket, BracketKind};
+use crate::expr::Expr;
+use crate::solver::{Iteration, Method, RootSolver, Run, SolverError};
+use crate::stop::StopCriteria;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// What the worker sends back to the UI.
+pub enum Message {
+    /// A new iteration and the solver state right after it.
+    Iteration(Iteration, Box<dyn RootSolver>),
+    /// The stop rules hold for the last iteration.
+    Reached,
+    Failed(SolverError),
+    Cancelled,
+    /// The run made the number of iterations it was asked to replay.
+    Replayed,
+}
+
+/// Everything a run needs, moved onto the worker thread.
+pub struct Job {
+    pub solver: Box<dyn RootSolver>,
+    pub expr: Expr,
+    pub method: Method,
+    pub analysis: Analysis,
+    pub stop: StopCriteria,
+    pub eps: f64,
+    /// The iteration cap the solver was given, to report progress against.
+    pub max_iterations: usize,
+    /// How many iterations happened before the job started.
+    pub done: usize,
+    /// The latest iterate before the job started.
+    pub x: f64,
+    pub budget: Duration,
+    /// The run pauses after this many iterations, `usize::MAX` to go on until it stops.
+    pub until: usize,
+}
+
+/// A solver running on a background thread so the UI stays responsive.
+pub struct Worker {
+    receiver: Receiver<Message>,
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+    budget: Duration,
+    max_iterations: usize,
+    /// Iterations received so far, including the ones before the job started.
+    pub iterations: usize,
+}
+
+impl Worker {
+    pub fn spawn(mut job: Job) -> Worker {
+        let (sender, receiver) = mpsc::channel::<Message>();
+        let cancel: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let cancelled: Arc<AtomicBool> = cancel.clone();
+        let started: Instant = Instant::now();
+        let worker: Worker = Worker {
+            receiver,
+            cancel,
+            started,
+            budget: job.budget,
+            max_iterations: job.max_iterations,
+            iterations: job.done,
+        };
+        std::thread::spawn(move || {
+            let mut n: usize = job.done;
+            let mut x: f64 = job.x;
+            let outcome: Message = loop {
+                if cancelled.load(Ordering::Relaxed) {
+                    break Message::Cancelled;
+                }
+                if n >= job.until {
+                    break Message::Replayed;
+                }
+                if started.elapsed() > job.budget {
+                    break Message::Failed(SolverError::TimeLimit {
+                        seconds: job.budget.as_secs_f64(),
+                        iterations: n,
+                        x,
+                    });
+                }
+                match job.solver.step(&job.expr) {
+                    Ok(iteration) => {
+                        let reached: bool =
+                            job.stop
+                                .stops(job.method, &iteration, &job.analysis, job.eps);
+                        n += 1;
+                        x = iteration.x;
+                        // The UI is gone when sending fails, so there is nobody to solve for.
+                        if sender
+                            .send(Message::Iteration(iteration, job.solver.snapshot()))
+                            .is_err()
+                        {
+                            return;
+                        }
+                        if reached {
+                            break Message::Reached;
+                        }
+                    }
+                    Err(error) => break Message::Failed(error),
+                }
+            };
+            let _ = sender.send(outcome);
+        });
+        worker
+    }
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    /// Messages that arrived since the last call; the last one may end the run.
+    pub fn poll(&mut self) -> Vec<Message> {
+        let mut messages: Vec<Message> = vec![];
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => {
+                    let last: bool = !matches!(message, Message::Iteration(..));
+                    if !last {
+                        self.iterations += 1;
+                    }
+                    messages.push(message);
+                    if last {
+                        break;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                // The thread ended without saying why, for example after a panic.
+                Err(TryRecvError::Disconnected) => {
+                    messages.push(Message::Cancelled);
+                    break;
+                }
+            }
+        }
+        messages
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+    /// How far the run is towards the iteration cap or the time budget, whichever is closer.
+    pub fn fraction(&self) -> f32 {
+        let by_iterations: f32 = self.iterations as f32 / self.max_iterations.max(1) as f32;
+        let by_time: f32 = self.elapsed().as_secs_f32() / self.budget.as_secs_f32().max(1e-3);
+        by_iterations.max(by_time).min(1.0)
+    }
+}
+
+/// Everything "Find all roots" needs, moved onto the worker thread.
+pub struct ScanJob {
+    pub expr: Expr,
+    pub method: Method,
+    pub brackets: Vec<Bracket>,
+    pub stop: StopCriteria,
+    pub eps: f64,
+    pub max_iterations: usize,
+    /// The time budget of each bracket.
+    pub budget: Duration,
+}
+
+/// Solves every bracket of a scan in turn on a background thread.
+pub struct Scan {
+    receiver: Receiver<Run>,
+    cancel: Arc<AtomicBool>,
+    pub total: usize,
+    pub done: usize,
+}
+
+impl Scan {
+    pub fn spawn(job: ScanJob) -> Scan {
+        let (sender, receiver) = mpsc::channel::<Run>();
+        let cancel: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let cancelled: Arc<AtomicBool> = cancel.clone();
+        let scan: Scan = Scan {
+            receiver,
+            cancel,
+            total: job.brackets.len(),
+            done: 0,
+        };
+        std::thread::spawn(move || {
+            for bracket in job.brackets.iter() {
+                let Some(run) = solve_bracket(&job, bracket, &cancelled) else {
+                    return;
+                };
+                if sender.send(run).is_err() {
+                    return;
+                }
+            }
+        });
+        scan
+    }
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    /// The runs finished since the last call.
+    pub fn poll(&mut self) -> Vec<Run> {
+        let runs: Vec<Run> = self.receiver.try_iter().collect();
+        self.done += runs.len();
+        runs
+    }
+    /// Every bracket is solved.
+    pub fn finished(&self) -> bool {
+        self.done == self.total
+    }
+    /// The scan was cancelled before every bracket was solved.
+    pub fn cancelled(&self) -> bool {
+        !self.finished() && self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// One run of a scan; `None` once the scan is cancelled.
+fn solve_bracket(job: &ScanJob, bracket: &Bracket, cancelled: &AtomicBool) -> Option<Run> {
+    // Without a sign change only the open methods can get to an even root.
+    let method: Method =
+        if bracket.kind == BracketKind::EvenMultiplicity && job.method.needs_sign_change() {
+            Method::Newton
+        } else {
+            job.method
+        };
+    let analysis: Analysis = analysis::analyze(&job.expr, bracket.a, bracket.b, 200);
+    let mut solver: Box<dyn RootSolver> = method.solver(bracket.a, bracket.b, job.eps);
+    solver.set_limits(job.eps, job.max_iterations);
+    let mut run: Run = Run {
+        method,
+        a: bracket.a,
+        b: bracket.b,
+        iterations: vec![],
+        error: None,
+    };
+    let started: Instant = Instant::now();
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+        if started.elapsed() > job.budget {
+            run.error = Some(SolverError::TimeLimit {
+                seconds: job.budget.as_secs_f64(),
+                iterations: run.iterations.len(),
+                x: run.iterations.last().map_or(f64::NAN, |last| last.x),
+            });
+            return Some(run);
+        }
+        match solver.step(&job.expr) {
+            Ok(mut iteration) => {
+                let stops: bool = job.stop.stops(method, &iteration, &analysis, job.eps);
+                // `Run::root` reads the outcome of the stop rules from the last iteration.
+                iteration.reached_eps = stops;
+                run.iterations.push(iteration);
+                if stops {
+                    return Some(run);
+                }
+            }
+            Err(error) => {
+                run.error = Some(error);
+                return Some(run);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+
+    #[test]
+    fn scan_uses_newton_where_there_is_no_sign_change() {
+        let expr: Expr = expr::parse("(x - 1)^2 * (x + 2)").unwrap();
+        let brackets: Vec<Bracket> = analysis::find_brackets(&expr, -5.0, 5.0, 200);
+        let job: ScanJob = ScanJob {
+            expr,
+            method: Method::Chord,
+            brackets: brackets.clone(),
+            stop: StopCriteria::default(),
+            eps: 1e-8,
+            max_iterations: 1000,
+            budget: Duration::from_secs(5),
+        };
+        let cancelled: AtomicBool = AtomicBool::new(false);
+        let runs: Vec<Run> = brackets
+            .iter()
+            .map(|bracket| solve_bracket(&job, bracket, &cancelled).unwrap())
+            .collect();
+        let roots: Vec<f64> = runs
+            .iter()
+            .filter_map(Run::root)
+            .map(|root| root.x)
+            .collect();
+        assert_eq!(
+            roots.len(),
+            2,
+            "{:?}",
+            runs.iter().map(|run| run.error).collect::<Vec<_>>()
+        );
+        assert!((roots[0] + 2.0).abs() < 1e-6);
+        assert!((roots[1] - 1.0).abs() < 1e-4);
+        assert_eq!(runs[1].method, Method::Newton);
+    }
+
+    #[test]
+    fn cancelled_scan_stops() {
+        let job: ScanJob = ScanJob {
+            expr: expr::parse("x").unwrap(),
+            method: Method::Bisection,
+            brackets: vec![],
+            stop: StopCriteria::default(),
+            eps: 1e-8,
+            max_iterations: 1000,
+            budget: Duration::from_secs(5),
+        };
+        let bracket: Bracket = Bracket {
+            a: -1.0,
+            b: 2.0,
+            kind: BracketKind::SignChange,
+        };
+        assert!(solve_bracket(&job, &bracket, &AtomicBool::new(true)).is_none());
+        let mut scan: Scan = Scan::spawn(ScanJob {
+            brackets: vec![bracket; 10_000],
+            ..job
+        });
+        scan.cancel();
+        scan.poll();
+        assert!(scan.cancelled());
+        assert!(!scan.finished());
+    }
+}